@@ -0,0 +1,403 @@
+use egui::{Color32, Response, Ui, Vec2, WidgetText};
+
+use super::assign::AssignPolicy;
+use super::format::format_ago;
+use super::steam::prelude::*;
+use super::App;
+
+/// Color used for accounts Steam won't log into without a password
+pub const NO_AUTO_LOGIN_COLOR: Color32 = Color32::from_rgb(230, 170, 60);
+
+const AVATAR_SIZE: f32 = 18.0;
+
+/// Account Settings
+///
+/// User preferences for an account, keyed by login name
+///
+/// # Variables
+///
+/// - `alias` - Name shown instead of the persona name, empty for none
+/// - `color` - Color of the account badge on game cards
+/// - `hidden` - Hide the account from pickers and account views
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct AccountSettings {
+    pub alias: String,
+    pub color: Option<Color32>,
+    pub hidden: bool,
+}
+
+/// Describe the login state of an account, shown when hovering over it
+pub fn account_tooltip(account: &SteamAccount) -> String {
+    let mut lines = vec![format!("Login name: {}", account.name())];
+    if let Some(id) = &account.id {
+        lines.push(format!("SteamID: {}", id.id64()));
+    }
+    match account.timestamp {
+        Some(timestamp) => lines.push(format!("Last login: {}", format_ago(timestamp))),
+        None => lines.push("Last login: unknown".to_string()),
+    }
+    if account.most_recent {
+        lines.push("Most recently used account".to_string());
+    }
+    if account.wants_offline_mode {
+        lines.push("Starts in offline mode".to_string());
+    }
+    if !account.remember_password {
+        lines.push("⚠ Password is not remembered, Steam will ask for it when switching".to_string());
+    } else if !account.allow_auto_login {
+        lines.push("⚠ Auto login is disabled, Steam will ask for the password when switching".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Paint the cached avatar of an account, or an empty space of the same size
+pub fn account_avatar(ui: &mut Ui, account: &SteamAccount) {
+    match &account.avatar {
+        Some(avatar) => {
+            ui.add(
+                egui::Image::new(format!("file://{}", avatar.to_string_lossy()))
+                    .fit_to_exact_size(Vec2::splat(AVATAR_SIZE))
+                    .rounding(3.0)
+            );
+        },
+        None => {
+            ui.allocate_space(Vec2::splat(AVATAR_SIZE));
+        },
+    }
+}
+
+/// A selectable combo box entry with the account's avatar in front
+pub fn account_option<V: PartialEq>(
+    ui: &mut Ui,
+    current: &mut V,
+    value: V,
+    account: &SteamAccount,
+    text: impl Into<WidgetText>,
+) -> Response {
+    let response = ui.horizontal(|ui| {
+        account_avatar(ui, account);
+        ui.selectable_value(current, value, text)
+    })
+    .inner
+    .on_hover_text(account_tooltip(account));
+    account_context_menu(&response, account);
+    response
+}
+
+/// Right click menu to copy the SteamID of an account in any format or open its profile
+pub fn account_context_menu(response: &Response, account: &SteamAccount) {
+    response.context_menu(|ui| {
+        if ui.button("Copy login name").clicked() {
+            ui.ctx().copy_text(account.name().to_string());
+            ui.close_menu();
+        }
+
+        let Some(id) = &account.id else {
+            return;
+        };
+        ui.separator();
+        for (label, text) in [
+            ("Copy SteamID64", id.id64().to_string()),
+            ("Copy SteamID", id.steam2()),
+            ("Copy SteamID3", id.steam3()),
+            ("Copy account id", id.account_id.to_string()),
+            ("Copy profile url", id.profile_url()),
+        ] {
+            if ui.button(label).on_hover_text(&text).clicked() {
+                ui.ctx().copy_text(text);
+                ui.close_menu();
+            }
+        }
+
+        ui.separator();
+        if ui.button("Open profile").clicked() {
+            if let Err(e) = open::that(id.profile_url()) {
+                log::error!("Failed to open profile: {}", e);
+            }
+            ui.close_menu();
+        }
+    });
+}
+
+/// An account waiting for the user to confirm forgetting it
+///
+/// # Variables
+///
+/// - `account` - The login name of the account
+/// - `userdata` - What to do with its `userdata` folder
+/// - `reassign` - Account to move its games to, `None` lets automatic assignment pick again
+pub struct ForgetAccount {
+    pub account: String,
+    pub userdata: UserdataAction,
+    pub reassign: Option<String>,
+}
+
+impl App {
+    fn account_settings(&self, account: &str) -> Option<&AccountSettings> {
+        self.account_settings.get(account)
+    }
+
+    pub(super) fn is_account_hidden(&self, account: &str) -> bool {
+        self.account_settings(account).is_some_and(|x| x.hidden)
+    }
+
+    pub(super) fn account_color(&self, account: &str) -> Option<Color32> {
+        self.account_settings(account).and_then(|x| x.color)
+    }
+
+    /// Get the alias of an account, falling back to its persona name
+    pub(super) fn account_name<'a>(&'a self, account: &'a SteamAccount) -> &'a str {
+        match self.account_settings(account.name()) {
+            Some(settings) if !settings.alias.trim().is_empty() => settings.alias.trim(),
+            _ => account.persona_name(),
+        }
+    }
+
+    /// Get the accounts that aren't hidden, in account order
+    pub(super) fn visible_accounts(&self) -> Vec<&SteamAccount> {
+        self.ordered_accounts()
+            .into_iter()
+            .filter(|x| !self.is_account_hidden(x.name()))
+            .collect()
+    }
+
+    /// Format an account as `Alias (login)`, flagging accounts that can't log in automatically
+    pub(super) fn account_text(&self, account: &SteamAccount) -> String {
+        let name = self.account_name(account);
+        let mut text = if name == account.name() {
+            account.name().to_string()
+        } else {
+            format!("{} ({})", name, account.name())
+        };
+        if !account.can_auto_login() {
+            text = format!("⚠ {}", text);
+        }
+        text
+    }
+
+    /// Text of an account for combo boxes, colored if it can't log in automatically
+    pub(super) fn account_rich_text(&self, account: &SteamAccount) -> egui::RichText {
+        let text = egui::RichText::new(self.account_text(account));
+        if account.can_auto_login() {
+            text
+        } else {
+            text.color(NO_AUTO_LOGIN_COLOR)
+        }
+    }
+
+    /// Show every account with its alias, color and visibility, in account priority order
+    pub(super) fn accounts_view(&mut self, ui: &mut egui::Ui) {
+        ui.heading(format!("Accounts ({})", self.steam_model.user_cache.len()));
+        ui.label("Hidden accounts are left out of account pickers and views, games saved to them still launch with them.");
+        ui.label(format!(
+            "Accounts are listed by priority. Games owned by several accounts are assigned to the first one with the \"{}\" policy.",
+            AssignPolicy::Priority,
+        ));
+        ui.separator();
+
+        self.account_order = self.ordered_accounts().iter().map(|x| x.name().to_string()).collect();
+        let accounts: Vec<SteamAccount> = self.ordered_accounts().into_iter().cloned().collect();
+
+        let mut swap = None;
+        egui::ScrollArea::vertical()
+            .id_salt("accounts")
+            .show(ui, |ui| {
+                egui::Grid::new("accounts_grid")
+                    .num_columns(8)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Priority");
+                        ui.strong("Account");
+                        ui.strong("Alias");
+                        ui.strong("Color");
+                        ui.strong("Hidden");
+                        ui.strong("Games");
+                        ui.strong("Last Login");
+                        ui.label("");
+                        ui.end_row();
+
+                        for (i, account) in accounts.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("{}.", i + 1));
+                                if ui.add_enabled(i > 0, egui::Button::new("⬆")).clicked() {
+                                    swap = Some((i, i - 1));
+                                }
+                                if ui.add_enabled(i + 1 < accounts.len(), egui::Button::new("⬇")).clicked() {
+                                    swap = Some((i, i + 1));
+                                }
+                            });
+
+                            let response = ui.horizontal(|ui| {
+                                account_avatar(ui, account);
+                                ui.label(self.account_rich_text(account))
+                            })
+                            .inner
+                            .on_hover_text(account_tooltip(account));
+                            account_context_menu(&response, account);
+
+                            let settings = self.account_settings.entry(account.name().to_string()).or_default();
+                            let mut changed = ui.add(
+                                egui::TextEdit::singleline(&mut settings.alias)
+                                    .hint_text(account.persona_name())
+                                    .desired_width(140.0)
+                            ).changed();
+
+                            ui.horizontal(|ui| {
+                                let mut enabled = settings.color.is_some();
+                                changed |= ui.checkbox(&mut enabled, "").changed();
+                                match (enabled, settings.color.as_mut()) {
+                                    (true, Some(color)) => changed |= ui.color_edit_button_srgba(color).changed(),
+                                    (true, None) => settings.color = Some(Color32::from_rgb(70, 110, 200)),
+                                    (false, _) => settings.color = None,
+                                }
+                            });
+
+                            changed |= ui.checkbox(&mut settings.hidden, "").changed();
+                            if changed {
+                                self.needs_save = true;
+                            }
+
+                            ui.label(account.games.len().to_string());
+                            match account.timestamp {
+                                Some(timestamp) => ui.label(format_ago(timestamp)),
+                                None => ui.weak("Unknown"),
+                            };
+                            if ui.button("Forget…").on_hover_text("Remove the account from the Steam login list").clicked() {
+                                self.forget_account = Some(ForgetAccount {
+                                    account: account.name().to_string(),
+                                    userdata: UserdataAction::Keep,
+                                    reassign: None,
+                                });
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some((a, b)) = swap {
+            self.account_order.swap(a, b);
+            self.needs_save = true;
+        }
+        self.account_settings.retain(|_, x| *x != AccountSettings::default());
+    }
+
+    /// Ask to confirm forgetting an account and where its games should go
+    pub(super) fn forget_window(&mut self, ctx: &egui::Context) {
+        let Some(forget) = &mut self.forget_account else {
+            return;
+        };
+
+        let games = self.saved_logins.values().filter(|x| **x == forget.account).count();
+        let others: Vec<String> = self.steam_model.user_cache
+            .iter()
+            .filter(|x| x.name != forget.account && !self.account_settings.get(&x.name).is_some_and(|x| x.hidden))
+            .map(|x| x.name.clone())
+            .collect();
+
+        let mut confirmed = None;
+        egui::Window::new("Forget Account")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("Remove \"{}\" from the Steam login list?", forget.account));
+                ui.label("Steam has to be closed. loginusers.vdf and the account's config are backed up first.");
+                ui.separator();
+
+                egui::ComboBox::from_label("Userdata folder")
+                    .selected_text(forget.userdata.to_string())
+                    .show_ui(ui, |ui| {
+                        for action in [UserdataAction::Keep, UserdataAction::Archive, UserdataAction::Delete] {
+                            ui.selectable_value(&mut forget.userdata, action, action.to_string());
+                        }
+                    });
+
+                if games > 0 {
+                    egui::ComboBox::from_label(format!("Move its {} games to", games))
+                        .selected_text(forget.reassign.as_deref().unwrap_or("Automatic assignment"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut forget.reassign, None, "Automatic assignment");
+                            for account in &others {
+                                ui.selectable_value(&mut forget.reassign, Some(account.clone()), account);
+                            }
+                        });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Forget").clicked() {
+                        confirmed = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        confirmed = Some(false);
+                    }
+                });
+            });
+
+        match confirmed {
+            Some(true) => {
+                if let Some(forget) = self.forget_account.take() {
+                    self.forget(forget);
+                }
+            },
+            Some(false) => self.forget_account = None,
+            None => {},
+        }
+    }
+
+    /// Forget an account and clean up everything referring to it
+    fn forget(&mut self, forget: ForgetAccount) {
+        let backup = match self.steam_model.forget_account(&forget.account, forget.userdata) {
+            Ok(backup) => backup,
+            Err(e) => {
+                log::error!("Failed to forget account '{}': {}", forget.account, e);
+                self.toasts.error(format!("Failed to forget account: {}", e));
+                return;
+            },
+        };
+
+        let apps: Vec<AppID> = self.saved_logins
+            .iter()
+            .filter(|(_, account)| **account == forget.account)
+            .map(|(app, _)| app.clone())
+            .collect();
+        for app in &apps {
+            match &forget.reassign {
+                Some(account) => {
+                    self.saved_logins.insert(app.clone(), account.clone());
+                },
+                None => {
+                    self.saved_logins.remove(app);
+                    self.manual_logins.remove(app);
+                },
+            }
+        }
+
+        self.account_order.retain(|x| *x != forget.account);
+        self.account_settings.remove(&forget.account);
+        self.launch_config.start_modes.remove(&forget.account);
+        if self.home_account.as_ref() == Some(&forget.account) {
+            self.home_account = None;
+        }
+        for rule in &mut self.rules {
+            if rule.account.as_ref() == Some(&forget.account) {
+                rule.account = None;
+                rule.enabled = false;
+            }
+        }
+        for profile in self.launch_profiles.values_mut().flatten() {
+            if profile.account.as_ref() == Some(&forget.account) {
+                profile.account = forget.reassign.clone();
+            }
+        }
+        if self.selected_account == forget.account {
+            self.selected_account = self.steam_model.user_cache.first().map(|x| x.name.clone()).unwrap_or_default();
+        }
+
+        self.assign_logins(false);
+        self.toasts.success(format!("Forgot \"{}\" and updated {} games, backup saved to {}", forget.account, apps.len(), backup.display()));
+        self.needs_save = true;
+    }
+}
@@ -0,0 +1,135 @@
+use core::fmt;
+use std::cmp::Reverse;
+
+use super::steam::prelude::*;
+use super::App;
+
+/// Assign Policy
+/// 
+/// How an account is chosen for a game that is owned by several accounts and has no manual choice
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssignPolicy {
+    #[default]
+    FirstFound,
+    MostPlaytime,
+    MostRecent,
+    Priority,
+    Ask,
+}
+
+impl fmt::Display for AssignPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssignPolicy::FirstFound => write!(f, "First found"),
+            AssignPolicy::MostPlaytime => write!(f, "Most play time"),
+            AssignPolicy::MostRecent => write!(f, "Most recently played"),
+            AssignPolicy::Priority => write!(f, "Account priority"),
+            AssignPolicy::Ask => write!(f, "Ask me"),
+        }
+    }
+}
+
+impl AssignPolicy {
+    pub const ALL: [AssignPolicy; 5] = [
+        AssignPolicy::FirstFound,
+        AssignPolicy::MostPlaytime,
+        AssignPolicy::MostRecent,
+        AssignPolicy::Priority,
+        AssignPolicy::Ask,
+    ];
+
+    /// Pick an account out of the owners of a game
+    /// 
+    /// `owners` are expected in detected order for [`AssignPolicy::FirstFound`] and in account priority order otherwise,
+    /// returns `None` if the choice is left to the user
+    pub fn pick<'a>(&self, owners: &[&'a SteamAccount], appid: &i32) -> Option<&'a SteamAccount> {
+        if owners.len() <= 1 {
+            return owners.first().copied();
+        }
+
+        match self {
+            AssignPolicy::FirstFound | AssignPolicy::Priority => owners.first().copied(),
+            // Ties keep the earlier account since `min_by_key` returns the first minimum
+            AssignPolicy::MostPlaytime => owners.iter().copied().min_by_key(|x| Reverse(x.playtime(appid))),
+            AssignPolicy::MostRecent => owners.iter().copied().min_by_key(|x| Reverse(x.last_played(appid))),
+            AssignPolicy::Ask => None,
+        }
+    }
+}
+
+impl App {
+    /// Get all accounts sorted by the account priority order
+    /// 
+    /// Accounts missing from the order keep their detected order after the ordered ones
+    pub(super) fn ordered_accounts(&self) -> Vec<&SteamAccount> {
+        let mut accounts: Vec<&SteamAccount> = self.steam_model.user_cache.iter().collect();
+        accounts.sort_by_key(|x| {
+            self.account_order
+                .iter()
+                .position(|name| name == x.name())
+                .unwrap_or(usize::MAX)
+        });
+        accounts
+    }
+
+    /// Mark saved mappings as manual once, when upgrading from a version that didn't track manual choices
+    /// 
+    /// Older versions saved the first detected owner of every game, only mappings that differ from it were picked by hand.
+    /// The others stay automatic, so re-evaluating can fix them
+    pub(super) fn seed_manual_logins(&mut self) {
+        if self.manual_logins_seeded {
+            return;
+        }
+        let accounts = &self.steam_model.user_cache;
+        let manual: Vec<AppID> = self.saved_logins
+            .iter()
+            .filter(|(app, account)| {
+                let first_found = accounts.iter().find(|x| x.games.contains(&app.id)).map(|x| x.name());
+                first_found != Some(account.as_str())
+            })
+            .map(|(app, _)| app.clone())
+            .collect();
+        self.manual_logins.extend(manual);
+        self.manual_logins_seeded = true;
+        self.needs_save = true;
+    }
+
+    /// Assign accounts to installed games using the assignment rules, then the assign policy
+    /// 
+    /// With `reevaluate` every automatic assignment is recalculated, manual choices are never touched.
+    /// Returns the number of games that got a new assignment
+    pub(super) fn assign_logins(&mut self, reevaluate: bool) -> usize {
+        if reevaluate {
+            self.saved_logins.retain(|app, _| self.manual_logins.contains(app));
+        }
+
+        self.refresh_rule_matcher();
+        // First found keeps the order Steam lists the accounts in, the other policies break ties by priority
+        let accounts: Vec<&SteamAccount> = match self.assign_policy {
+            AssignPolicy::FirstFound => self.steam_model.user_cache.iter().collect(),
+            _ => self.ordered_accounts(),
+        };
+        let mut assigned = Vec::new();
+        for game in self.steam_model.get_installed_apps() {
+            if self.saved_logins.contains_key(&game) {
+                continue;
+            }
+
+            let owners: Vec<&SteamAccount> = accounts
+                .iter()
+                .copied()
+                .filter(|x| x.games.contains(&game.id))
+                .collect();
+            if let Some((_, account)) = self.rule_matcher.find(&game, self.steam_model.app_info.get(&game.id), &owners) {
+                assigned.push((game, account));
+            } else if let Some(account) = self.assign_policy.pick(&owners, &game.id) {
+                assigned.push((game, account.name().to_string()));
+            }
+        }
+
+        let count = assigned.len();
+        self.saved_logins.extend(assigned);
+        count
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::steam::prelude::*;
+
+/// Current unix timestamp in seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default()
+}
+
+/// Format a number of seconds as hours and minutes, e.g. `12h 30m`
+pub fn format_duration(secs: u64) -> String {
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Format a number of bytes with a binary unit, e.g. `1.5 GiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Format a unix timestamp relative to now, e.g. `3 days ago`
+pub fn format_ago(timestamp: u64) -> String {
+    let elapsed = now().saturating_sub(timestamp);
+
+    let (amount, unit) = match elapsed {
+        0..=59 => return "just now".to_string(),
+        60..=3599 => (elapsed / 60, "minute"),
+        3600..=86399 => (elapsed / 3600, "hour"),
+        86400..=2591999 => (elapsed / 86400, "day"),
+        2592000..=31535999 => (elapsed / 2592000, "month"),
+        _ => (elapsed / 31536000, "year"),
+    };
+
+    if amount == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", amount, unit)
+    }
+}
+
+/// Summarize an account's play time and last played date of a game, e.g. `120h, last 3 days ago`
+pub fn format_stats(account: &SteamAccount, appid: &i32) -> String {
+    let playtime = account.playtime(appid);
+    match account.last_played(appid) {
+        Some(last_played) => format!("{}, last {}", format_duration(playtime * 60), format_ago(last_played)),
+        None if playtime > 0 => format_duration(playtime * 60),
+        None => "never played".to_string(),
+    }
+}
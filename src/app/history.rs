@@ -0,0 +1,384 @@
+use std::{cmp::Reverse, collections::{HashMap, HashSet}, time::{Duration, Instant}};
+
+use super::format::{format_ago, format_duration, now};
+use super::steam::prelude::*;
+use super::App;
+
+/// Session
+/// 
+/// A single play session of a game launched through wait
+/// 
+/// # Variables
+/// 
+/// - `app` - The launched game
+/// - `account` - The account the game was launched with
+/// - `start` - Unix timestamp of the game being seen running
+/// - `end` - Unix timestamp of the game exiting, `None` while running or if the exit was missed
+/// - `active` - Whether the session was started in this run of wait and can still be ended
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub app: AppID,
+    pub account: String,
+    pub start: u64,
+    pub end: Option<u64>,
+    #[serde(skip)]
+    pub active: bool,
+}
+
+impl Session {
+    pub fn duration(&self) -> Option<u64> {
+        self.end.map(|end| end.saturating_sub(self.start))
+    }
+}
+
+/// Totals
+/// 
+/// Accumulated sessions and play time
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Totals {
+    pub sessions: usize,
+    pub playtime: u64,
+    pub last_start: u64,
+}
+
+impl Totals {
+    fn add(&mut self, session: &Session) {
+        self.sessions += 1;
+        self.playtime += session.duration().unwrap_or_default();
+        self.last_start = self.last_start.max(session.start);
+    }
+}
+
+/// History
+/// 
+/// Log of every game launched through wait
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Default, Clone)]
+#[serde(default)]
+pub struct History {
+    pub sessions: Vec<Session>,
+    /// Games launched through wait that haven't been seen running yet
+    #[serde(skip)]
+    pending: HashMap<i32, (AppID, String, Instant)>,
+}
+
+impl History {
+    /// Remember a launched game, its session starts once the game is seen running
+    pub fn expect(&mut self, app: AppID, account: String) {
+        self.pending.insert(app.id, (app, account, Instant::now()));
+    }
+
+    /// Start the session of a launched game that is now running
+    /// 
+    /// Returns `true` if a session was started
+    pub fn seen_running(&mut self, appid: i32) -> bool {
+        match self.pending.remove(&appid) {
+            Some((app, account, _)) => {
+                self.start(app, account);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Forget launched games that didn't start running within `timeout`
+    pub fn expire_pending(&mut self, timeout: Duration) {
+        self.pending.retain(|_, (_, _, launched)| launched.elapsed() < timeout);
+    }
+
+    /// Pick up sessions left open by a previous run of wait
+    /// 
+    /// The latest open session of a game that is still running becomes active again,
+    /// any other open session had its exit missed and stays without an end
+    pub fn resume(&mut self, running: &HashSet<i32>) {
+        let mut resumed = HashSet::new();
+        for session in self.sessions.iter_mut().rev().filter(|x| x.end.is_none()) {
+            if running.contains(&session.app.id) && resumed.insert(session.app.id) {
+                session.active = true;
+            }
+        }
+    }
+
+    /// Open a new session for a game
+    pub fn start(&mut self, app: AppID, account: String) {
+        self.end(app.id);
+        self.sessions.push(Session {
+            app,
+            account,
+            start: now(),
+            end: None,
+            active: true,
+        });
+    }
+
+    /// End the active session of a game
+    pub fn end(&mut self, appid: i32) {
+        for session in self.sessions.iter_mut().filter(|x| x.active && x.app.id == appid) {
+            session.end = Some(now());
+            session.active = false;
+        }
+    }
+
+    pub fn is_active(&self, appid: i32) -> bool {
+        self.sessions.iter().any(|x| x.active && x.app.id == appid)
+    }
+
+    /// Get the most recently launched games, most recent first
+    pub fn recent(&self, count: usize) -> Vec<AppID> {
+        let mut recent: Vec<AppID> = Vec::new();
+        for session in self.sessions.iter().rev() {
+            if !recent.iter().any(|x| x.id == session.app.id) {
+                recent.push(session.app.clone());
+            }
+            if recent.len() >= count {
+                break;
+            }
+        }
+        recent
+    }
+
+    pub fn totals_by_game(&self) -> Vec<(AppID, Totals)> {
+        let mut totals: HashMap<i32, (AppID, Totals)> = HashMap::new();
+        for session in &self.sessions {
+            totals.entry(session.app.id).or_insert_with(|| (session.app.clone(), Totals::default())).1.add(session);
+        }
+        totals.into_values().collect()
+    }
+
+    /// Total up sessions per account, leaving out accounts for which `hidden` is `true`
+    pub fn totals_by_account(&self, hidden: impl Fn(&str) -> bool) -> HashMap<String, Totals> {
+        let mut totals: HashMap<String, Totals> = HashMap::new();
+        for session in self.sessions.iter().filter(|x| !hidden(&x.account)) {
+            totals.entry(session.account.clone()).or_default().add(session);
+        }
+        totals
+    }
+}
+
+impl App {
+    /// Show the play session history with per game and per account totals
+    pub(super) fn history_view(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("History");
+            ui.label(format!("{} sessions", self.history.sessions.len()));
+            if ui.button("Clear").clicked() {
+                self.history.sessions.retain(|x| x.active);
+                self.needs_save = true;
+            }
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_salt("history")
+            .show(ui, |ui| {
+                egui::CollapsingHeader::new("Games")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let mut games = self.history.totals_by_game();
+                        games.sort_by_key(|(_, totals)| Reverse(totals.playtime));
+
+                        egui::Grid::new("history_games")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Game");
+                                ui.strong("Sessions");
+                                ui.strong("Play Time");
+                                ui.strong("Last Launched");
+                                ui.end_row();
+
+                                for (app, totals) in games {
+                                    ui.label(&app.name);
+                                    ui.label(totals.sessions.to_string());
+                                    ui.label(format_duration(totals.playtime));
+                                    ui.label(format_ago(totals.last_start));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                egui::CollapsingHeader::new("Accounts")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let mut accounts: Vec<(String, Totals)> = self.history
+                            .totals_by_account(|account| self.is_account_hidden(account))
+                            .into_iter()
+                            .collect();
+                        accounts.sort_by_key(|(_, totals)| Reverse(totals.playtime));
+
+                        egui::Grid::new("history_accounts")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Account");
+                                ui.strong("Sessions");
+                                ui.strong("Play Time");
+                                ui.strong("Last Launched");
+                                ui.end_row();
+
+                                for (account, totals) in accounts {
+                                    ui.label(account);
+                                    ui.label(totals.sessions.to_string());
+                                    ui.label(format_duration(totals.playtime));
+                                    ui.label(format_ago(totals.last_start));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                egui::CollapsingHeader::new("Sessions")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        egui::Grid::new("history_sessions")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Game");
+                                ui.strong("Account");
+                                ui.strong("Launched");
+                                ui.strong("Duration");
+                                ui.end_row();
+
+                                for session in self.history.sessions.iter().rev() {
+                                    ui.label(&session.app.name);
+                                    ui.label(&session.account);
+                                    ui.label(format_ago(session.start));
+                                    match session.duration() {
+                                        Some(duration) => ui.label(format_duration(duration)),
+                                        None if session.active => ui.label("Running"),
+                                        None => ui.label("Unknown").on_hover_text("wait was closed before the game exited"),
+                                    };
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(id: i32) -> AppID {
+        AppID {
+            id,
+            name: format!("Game {id}"),
+            location: Default::default(),
+            last_played: None,
+        }
+    }
+
+    fn session(id: i32, account: &str, start: u64, end: Option<u64>) -> Session {
+        Session {
+            app: app(id),
+            account: account.to_string(),
+            start,
+            end,
+            active: false,
+        }
+    }
+
+    #[test]
+    fn session_starts_once_seen_running() {
+        let mut history = History::default();
+        assert!(!history.seen_running(10));
+
+        history.expect(app(10), "alice".to_string());
+        assert!(history.sessions.is_empty());
+        assert!(history.seen_running(10));
+        assert!(!history.seen_running(10));
+
+        assert_eq!(history.sessions.len(), 1);
+        assert_eq!(history.sessions[0].account, "alice");
+        assert_eq!(history.sessions[0].end, None);
+        assert!(history.is_active(10));
+    }
+
+    #[test]
+    fn expired_launches_never_start() {
+        let mut history = History::default();
+        history.expect(app(10), "alice".to_string());
+        history.expire_pending(Duration::ZERO);
+        assert!(!history.seen_running(10));
+        assert!(history.sessions.is_empty());
+    }
+
+    #[test]
+    fn end_closes_the_active_session() {
+        let mut history = History::default();
+        history.start(app(10), "alice".to_string());
+        history.start(app(20), "bob".to_string());
+        history.end(10);
+
+        assert!(!history.is_active(10));
+        assert!(history.sessions[0].end.is_some());
+        assert!(history.is_active(20));
+        assert_eq!(history.sessions[1].end, None);
+    }
+
+    #[test]
+    fn restarting_a_game_ends_its_previous_session() {
+        let mut history = History::default();
+        history.start(app(10), "alice".to_string());
+        history.start(app(10), "bob".to_string());
+
+        assert_eq!(history.sessions.len(), 2);
+        assert!(history.sessions[0].end.is_some());
+        assert!(!history.sessions[0].active);
+        assert!(history.sessions[1].active);
+    }
+
+    #[test]
+    fn resume_reactivates_latest_open_session_of_running_games() {
+        let mut history = History::default();
+        history.sessions = vec![
+            session(10, "alice", 100, None),
+            session(10, "alice", 200, None),
+            session(20, "bob", 300, None),
+            session(30, "bob", 400, Some(500)),
+        ];
+        history.resume(&HashSet::from([10, 30]));
+
+        assert!(!history.sessions[0].active);
+        assert!(history.sessions[1].active);
+        assert!(!history.sessions[2].active);
+        assert!(!history.sessions[3].active);
+
+        history.end(10);
+        assert!(history.sessions[1].end.is_some());
+        assert_eq!(history.sessions[0].end, None);
+    }
+
+    #[test]
+    fn totals_per_account() {
+        let mut history = History::default();
+        history.sessions = vec![
+            session(10, "alice", 100, Some(160)),
+            session(20, "alice", 300, Some(330)),
+            session(10, "bob", 200, None),
+            session(20, "carol", 400, Some(500)),
+        ];
+
+        let totals = history.totals_by_account(|_| false);
+        assert_eq!(totals.len(), 3);
+        assert_eq!(totals["alice"], Totals { sessions: 2, playtime: 90, last_start: 300 });
+        assert_eq!(totals["bob"], Totals { sessions: 1, playtime: 0, last_start: 200 });
+        assert_eq!(totals["carol"], Totals { sessions: 1, playtime: 100, last_start: 400 });
+    }
+
+    #[test]
+    fn totals_skip_hidden_accounts() {
+        let mut history = History::default();
+        history.sessions = vec![
+            session(10, "alice", 100, Some(160)),
+            session(10, "bob", 200, Some(260)),
+        ];
+
+        let totals = history.totals_by_account(|account| account == "bob");
+        assert_eq!(totals.len(), 1);
+        assert!(totals.contains_key("alice"));
+        assert!(!totals.contains_key("bob"));
+    }
+}
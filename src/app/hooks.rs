@@ -0,0 +1,52 @@
+use super::steam::prelude::*;
+use super::App;
+
+/// Environment variables every hook gets, shown next to the hook editors
+const HOOK_ENV_HELP: &str = "Commands run through the shell with these variables set:\n\
+    WAIT_HOOK (pre-launch or post-exit), WAIT_APPID, WAIT_GAME_NAME, WAIT_INSTALL_PATH,\n\
+    WAIT_ACCOUNT, WAIT_RESTORE_ACCOUNT and WAIT_STEAM_PATH.\n\
+    A pre-launch hook exiting with a non zero code aborts the launch.";
+
+/// Edit the commands of a set of hooks, returns whether anything changed
+pub fn hooks_editor(ui: &mut egui::Ui, hooks: &mut Hooks, id_salt: impl std::hash::Hash) -> bool {
+    let mut changed = false;
+
+    egui::Grid::new(id_salt)
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Before launch")
+                .on_hover_text(HOOK_ENV_HELP);
+            changed |= ui.add(egui::TextEdit::singleline(&mut hooks.pre_launch).hint_text("command")).changed();
+            ui.end_row();
+
+            ui.label("After exit")
+                .on_hover_text(HOOK_ENV_HELP);
+            changed |= ui.add(egui::TextEdit::singleline(&mut hooks.post_exit).hint_text("command")).changed();
+            ui.end_row();
+        });
+
+    changed
+}
+
+impl App {
+    /// Show the editor for the hooks of a game
+    pub(super) fn game_hooks_section(&mut self, ui: &mut egui::Ui, app: &AppID) {
+        let mut hooks = self.launch_config.game_hooks.get(&app.id).cloned().unwrap_or_default();
+        let title = if hooks.is_empty() { "Hooks" } else { "Hooks (set)" };
+
+        egui::CollapsingHeader::new(title)
+            .id_salt(("game_hooks", app.id))
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.weak("Run after the global hooks from the settings");
+                if hooks_editor(ui, &mut hooks, ("game_hooks_grid", app.id)) {
+                    if hooks.is_empty() {
+                        self.launch_config.game_hooks.remove(&app.id);
+                    } else {
+                        self.launch_config.game_hooks.insert(app.id, hooks.clone());
+                    }
+                    self.needs_save = true;
+                }
+            });
+    }
+}
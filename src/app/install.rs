@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use egui::Color32;
+
+use super::format::format_bytes;
+use super::steam::prelude::*;
+use super::{App, PendingAction};
+
+/// Get the badge color of an install status, `None` for states that don't need attention
+pub fn status_color(status: InstallStatus) -> Option<Color32> {
+    match status {
+        InstallStatus::Installed | InstallStatus::Unknown => None,
+        InstallStatus::UpdateRequired => Some(Color32::from_rgb(200, 150, 40)),
+        InstallStatus::Updating | InstallStatus::Validating => Some(Color32::from_rgb(50, 110, 190)),
+        InstallStatus::Paused => Some(Color32::from_rgb(110, 110, 110)),
+        InstallStatus::FilesMissing | InstallStatus::Corrupt | InstallStatus::Uninstalling | InstallStatus::Uninstalled => {
+            Some(Color32::from_rgb(180, 50, 50))
+        },
+    }
+}
+
+/// A game owned by at least one account that isn't installed
+pub struct NotInstalled {
+    pub id: i32,
+    pub name: String,
+    pub owners: Vec<String>,
+}
+
+impl App {
+    /// Show the install state of a game with its update progress
+    pub(super) fn install_state_ui(&self, ui: &mut egui::Ui, app: &AppID) {
+        let state = self.steam_model.install_state(app);
+        let status = state.status();
+
+        match status_color(status) {
+            Some(color) => ui.colored_label(color, status.to_string()),
+            None => ui.label(status.to_string()),
+        }
+        .on_hover_text(format!("StateFlags: {}", state.flags));
+
+        if state.needs_update() {
+            if let Some(progress) = state.progress() {
+                ui.add(
+                    egui::ProgressBar::new(progress)
+                        .text(format!("{} / {}", format_bytes(state.bytes_downloaded), format_bytes(state.bytes_to_download)))
+                );
+            }
+            if state.target_build_id != 0 && state.target_build_id != state.build_id {
+                ui.weak(format!("Build {} → {}", state.build_id, state.target_build_id));
+            }
+        }
+        if state.update_result != 0 {
+            ui.colored_label(Color32::from_rgb(180, 50, 50), format!("Last update failed (error {})", state.update_result));
+        }
+    }
+
+    /// Get all games owned by a visible account that aren't installed, sorted by name
+    ///
+    /// Names come from `appinfo.vdf`, apps it doesn't list as games (tools, dlc, soundtracks) are skipped
+    pub(super) fn not_installed(&self) -> Vec<NotInstalled> {
+        let installed: HashSet<i32> = self.steam_model.games.keys().map(|x| x.id).collect();
+        let accounts = self.visible_accounts();
+
+        let mut apps: Vec<NotInstalled> = self.steam_model.app_info
+            .iter()
+            .filter(|(id, info)| info.kind == "game" && !info.name.is_empty() && !installed.contains(id))
+            .filter_map(|(id, info)| {
+                let owners: Vec<String> = accounts
+                    .iter()
+                    .filter(|x| x.games.contains(id))
+                    .map(|x| x.name().to_string())
+                    .collect();
+                if owners.is_empty() {
+                    return None;
+                }
+                Some(NotInstalled { id: *id, name: info.name.clone(), owners })
+            })
+            .collect();
+        apps.sort_by_key(|x| x.name.to_lowercase());
+        apps
+    }
+
+    /// Install a game with an account, asking for confirmation first if a game is running
+    pub(super) fn install(&mut self, appid: i32, account: String) {
+        if self.confirm_restart(&account, &[]) {
+            self.pending_action = Some(PendingAction::Install(appid, account));
+            return;
+        }
+
+        self.start_install(appid, account);
+    }
+
+    /// Start the launch pipeline to install a game with an account
+    pub(super) fn start_install(&mut self, appid: i32, account: String) {
+        if self.launch_task.is_some() {
+            self.toasts.warning("A launch is already in progress");
+            return;
+        }
+
+        let name = self.steam_model.app_info.get(&appid).map(|x| x.name.clone()).unwrap_or(appid.to_string());
+        self.toasts.info(format!("Installing {} as {}", name, account));
+        self.launch_task = Some(self.steam_model.install_game(&account, &appid, &self.launch_config));
+    }
+
+    /// Run a Steam client action for a game under its saved account, asking for confirmation first if a game is running
+    pub(super) fn client_action(&mut self, app: &AppID, action: ClientAction) {
+        let Some(account) = self.saved_logins.get(app) else {
+            self.toasts.warning(format!("Choose an account for {} first", app.name));
+            self.selected_app = Some(app.clone());
+            return;
+        };
+        if self.confirm_restart(account, &[]) {
+            self.pending_action = Some(PendingAction::Action(app.clone(), action));
+            return;
+        }
+
+        self.start_client_action(app, action);
+    }
+
+    /// Start the launch pipeline to run a client action for a game
+    pub(super) fn start_client_action(&mut self, app: &AppID, action: ClientAction) {
+        if self.launch_task.is_some() {
+            self.toasts.warning("A launch is already in progress");
+            return;
+        }
+        let Some(account) = self.saved_logins.get(app) else {
+            return;
+        };
+
+        self.toasts.info(format!("{}: {} as {}", action, app.name, account));
+        self.launch_task = Some(self.steam_model.client_action(account, &app.id, action, &self.launch_config));
+    }
+
+    /// Show the games owned by an account that aren't installed, with a button to install each
+    ///
+    /// The list is only collected while the section is open
+    pub(super) fn not_installed_section(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Not Installed")
+            .default_open(false)
+            .show(ui, |ui| {
+                if self.steam_model.app_info.is_empty() {
+                    ui.weak("appinfo.vdf could not be read, game names are unknown");
+                    return;
+                }
+
+                let search = self.search_filter.to_lowercase();
+                let apps: Vec<NotInstalled> = self.not_installed()
+                    .into_iter()
+                    .filter(|x| x.name.to_lowercase().contains(&search))
+                    .collect();
+                ui.weak(format!("{} games", apps.len()));
+
+                egui::Grid::new("not_installed_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for app in apps {
+                            ui.label(&app.name);
+                            ui.label(app.owners.join(", "));
+
+                            if let [owner] = &app.owners[..] {
+                                if ui.button("Install").clicked() {
+                                    self.install(app.id, owner.clone());
+                                }
+                            } else {
+                                ui.menu_button("Install as…", |ui| {
+                                    for owner in &app.owners {
+                                        if ui.button(owner).clicked() {
+                                            self.install(app.id, owner.clone());
+                                            ui.close_menu();
+                                        }
+                                    }
+                                });
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
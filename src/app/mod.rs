@@ -1,26 +1,35 @@
 pub mod steam;
+mod accounts;
+use accounts::{account_context_menu, account_option, AccountSettings, ForgetAccount, NO_AUTO_LOGIN_COLOR};
+mod assign;
+use assign::AssignPolicy;
+mod format;
+use format::format_stats;
+mod history;
+mod hooks;
+mod install;
+use install::status_color;
+use history::History;
+mod ownership;
+use ownership::{Ownership, NOT_OWNED_COLOR};
+mod profiles;
+mod restore;
+mod rules;
+use rules::{Rule, RuleMatcher};
+mod settings;
+mod storage;
+use storage::{library_label, StorageTable};
 mod widgets;
-use widgets::theme_popup;
+use widgets::{badges, theme_popup};
 
 use egui::{Align, Color32, ImageSource, Layout, Vec2};
 use egui_json_tree::JsonTree;
 use egui_notify::Toasts;
 use steam::prelude::*;
+use crate::cli::Cli;
 
 use core::{f32, fmt};
-use std::{collections::HashMap, ops::{Mul, Sub}};
-// use std::sync::mpsc::{Sender, Receiver, channel};
-
-macro_rules! steam_launch {
-    ($steam_model:expr, $account:expr, $app:expr, $close_after:expr) => {
-        match $steam_model.launch_game($account, &$app.id, $close_after) {
-            Ok(_) => {},
-            Err(e) => {
-                log::error!("Launch Error: {}", e);
-            }
-        }
-    };
-}
+use std::{collections::{HashMap, HashSet}, ops::{Mul, Sub}, path::PathBuf, time::{Duration, Instant}};
 
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
@@ -28,11 +37,27 @@ pub struct App {
     favorites: Vec<AppID>,
     hidden: Vec<AppID>,
     saved_logins: HashMap<AppID, String>,
+    manual_logins: HashSet<AppID>,
+    manual_logins_seeded: bool,
+    assign_policy: AssignPolicy,
+    account_order: Vec<String>,
+    account_settings: HashMap<String, AccountSettings>,
+    rules: Vec<Rule>,
     thumbnail_mode: ThumbnailMode,
     grid_size: f32,
     close_after: CloseAfter,
     theme: Theme,
     sort_mode: SortMode,
+    library_filter: Option<PathBuf>,
+    group_by_folder: bool,
+    launch_config: LaunchConfig,
+    restore_all: bool,
+    restore_games: HashSet<AppID>,
+    launch_profiles: HashMap<AppID, Vec<LaunchProfile>>,
+    home_account: Option<String>,
+    history: History,
+    dry_run: bool,
+    backup_days: u64,
 
     #[serde(skip)]
     steam_model: SteamModel,
@@ -45,12 +70,50 @@ pub struct App {
     #[serde(skip)]
     search_filter: String,
     #[serde(skip)]
+    needs_update_filter: bool,
+    #[serde(skip)]
+    manifests_reloaded: Instant,
+    #[serde(skip)]
+    disks: sysinfo::Disks,
+    #[serde(skip)]
+    disks_refreshed: Option<Instant>,
+    #[serde(skip)]
+    storage: Option<StorageTable>,
+    #[serde(skip)]
     toasts: Toasts,
-    // #[serde(skip)]
-    // toast_channel: (Sender<String>, Receiver<String>),
+    #[serde(skip)]
+    launch_task: Option<LaunchHandle>,
+    #[serde(skip)]
+    exit_watches: Vec<LaunchHandle>,
+    #[serde(skip)]
+    process_monitor: ProcessMonitor,
+    #[serde(skip)]
+    running: HashSet<i32>,
+    #[serde(skip)]
+    pending_action: Option<PendingAction>,
+    #[serde(skip)]
+    forget_account: Option<ForgetAccount>,
+    #[serde(skip)]
+    dry_run_log: Vec<String>,
+    #[serde(skip)]
+    journal: Vec<JournalEntry>,
+    #[serde(skip)]
+    journal_reloaded: Option<Instant>,
+    #[serde(skip)]
+    launch_options_edit: HashMap<(String, i32), String>,
+    #[serde(skip)]
+    profile_name_edit: HashMap<(i32, usize), String>,
+    #[serde(skip)]
+    ownership: Ownership,
+    #[serde(skip)]
+    rule_matcher: RuleMatcher,
+    #[serde(skip)]
+    view: View,
     #[serde(skip)]
     theme_popup: bool,
     #[serde(skip)]
+    settings_open: bool,
+    #[serde(skip)]
     needs_save: bool,
 }
 
@@ -60,20 +123,55 @@ impl Default for App {
             favorites: Vec::new(),
             hidden: Vec::new(),
             saved_logins: HashMap::new(),
+            manual_logins: HashSet::new(),
+            manual_logins_seeded: false,
+            assign_policy: AssignPolicy::default(),
+            account_order: Vec::new(),
+            account_settings: HashMap::new(),
+            rules: Vec::new(),
             thumbnail_mode: ThumbnailMode::Portrait,
             grid_size: 200.0,
             close_after: CloseAfter::None,
             theme: Theme::default(),
             sort_mode: SortMode::default(),
+            library_filter: None,
+            group_by_folder: false,
+            launch_config: LaunchConfig::default(),
+            restore_all: false,
+            restore_games: HashSet::new(),
+            launch_profiles: HashMap::new(),
+            home_account: None,
+            history: History::default(),
+            dry_run: false,
+            backup_days: 30,
+            journal: Vec::new(),
+            journal_reloaded: None,
+            dry_run_log: Vec::new(),
             theme_popup: false,
+            settings_open: false,
             steam_model: SteamModel::default(),
             thumbnail_cache: HashMap::new(),
             selected_account: String::default(),
             selected_app: None,
             search_filter: String::default(),
+            needs_update_filter: false,
+            manifests_reloaded: Instant::now(),
+            disks: sysinfo::Disks::new(),
+            disks_refreshed: None,
+            storage: None,
             toasts: Toasts::default(),
+            launch_task: None,
+            exit_watches: Vec::new(),
+            process_monitor: ProcessMonitor::default(),
+            running: HashSet::new(),
+            pending_action: None,
+            forget_account: None,
+            launch_options_edit: HashMap::new(),
+            profile_name_edit: HashMap::new(),
+            ownership: Ownership::default(),
+            rule_matcher: RuleMatcher::default(),
+            view: View::default(),
             needs_save: false,
-            // toast_channel: channel(),
         }
     }
 }
@@ -96,6 +194,27 @@ enum CloseAfter {
     Both
 }
 
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum View {
+    #[default]
+    Library,
+    History,
+    Ownership,
+    Conflicts,
+    Accounts,
+    Storage,
+    Restore,
+}
+
+/// An action that restarts Steam, waiting for confirmation because a game is running
+#[derive(Clone, PartialEq, Eq)]
+enum PendingAction {
+    Launch(AppID, Option<String>),
+    Login(String),
+    Install(i32, String),
+    Action(AppID, ClientAction),
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 #[derive(Clone, PartialEq, Eq, Hash)]
 struct Theme {
@@ -111,6 +230,7 @@ enum SortMode {
     #[default]
     LastPlayed,
     Name,
+    Playtime,
 }
 
 impl fmt::Display for SortMode {
@@ -118,6 +238,7 @@ impl fmt::Display for SortMode {
         match self {
             SortMode::LastPlayed => write!(f, "Last Played"),
             SortMode::Name => write!(f, "Name"),
+            SortMode::Playtime => write!(f, "Play Time"),
         }
     }
 }
@@ -145,7 +266,7 @@ impl fmt::Display for CloseAfter {
 }
 
 impl App {
-    pub fn new(cc: &eframe::CreationContext<'_>, steam_model: SteamModel, created: std::time::Instant) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, steam_model: SteamModel, created: std::time::Instant, cli: Cli) -> Self {
         // Update thumbnail cache
         let thumbnail_cache: HashMap<AppID, Thumbnail> = steam_model
             .get_installed_apps()
@@ -196,20 +317,35 @@ impl App {
             log::info!("No persisted state found. Applying default state");
         }
         
+        app.seed_manual_logins();
+
         // Initialize saved_logins for all detected games
-        for steam_account in &app.steam_model.user_cache {
-            for game in app.steam_model.get_installed_apps() {
-                // Check if this account owns this game and if no account is already assigned
-                if steam_account.games.contains(&game.id) && !app.saved_logins.contains_key(&game) {
-                    app.saved_logins.insert(game.clone(), steam_account.name().to_string());
-                }
-            }
-        }
+        app.assign_logins(false);
         
         app.toasts.info(format!("Initialized saved_logins for {} games", app.saved_logins.len()));
+
+        app.steam_model.dry_run = app.dry_run || cli.dry_run;
+        match app.steam_model.prune_backups(app.backup_days) {
+            Ok(0) => {},
+            Ok(removed) => log::info!("Pruned {} old journal entries", removed),
+            Err(e) => log::warn!("Failed to prune backups: {}", e),
+        }
+        app.process_monitor = ProcessMonitor::start(&app.steam_model);
+        app.running = app.process_monitor.running();
+        app.history.resume(&app.running);
         
         app.toasts.info(format!("Application loaded in {}ms", created.elapsed().as_millis()));
 
+        if let Some(appid) = cli.launch {
+            match app.steam_model.get_installed_apps().into_iter().find(|x| x.id == appid) {
+                Some(game) => app.launch_app(&game, cli.profile),
+                None => {
+                    log::error!("Launch Error: {} is not installed", appid);
+                    app.toasts.error(format!("Launch Error: {} is not installed", appid));
+                },
+            }
+        }
+
         app
     }
 }
@@ -221,6 +357,41 @@ impl eframe::App for App {
 
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         self.toasts.show(ctx);
+        self.poll_launch_task(ctx);
+        self.poll_exit_watches();
+        self.refresh_ownership();
+
+        // Start sessions of launched games once they run, end those of games that stopped running
+        let running = self.process_monitor.running();
+        self.history.expire_pending(Duration::from_secs(self.launch_config.game_timeout));
+        for appid in &running {
+            if self.history.seen_running(*appid) {
+                self.needs_save = true;
+            }
+        }
+        for appid in self.running.difference(&running) {
+            if self.history.is_active(*appid) {
+                self.history.end(*appid);
+                self.needs_save = true;
+            }
+        }
+        self.running = running;
+        ctx.request_repaint_after(Duration::from_secs(2));
+
+        // Pick up update and download progress
+        if self.manifests_reloaded.elapsed() > Duration::from_secs(10) {
+            if self.steam_model.reload_manifests() {
+                self.storage = None;
+            }
+            self.manifests_reloaded = Instant::now();
+        }
+
+        // Free space changes slowly, listing the disks every frame is too slow
+        if self.disks_refreshed.is_none_or(|x| x.elapsed() > Duration::from_secs(30)) {
+            self.disks.refresh_list();
+            self.disks_refreshed = Some(Instant::now());
+            self.storage = None;
+        }
 
         // Update theme
         let mut visuals = egui::Visuals::dark();
@@ -247,36 +418,95 @@ impl eframe::App for App {
                 ui.vertical_centered_justified(|ui| {
                     ui.heading("Steam Account");
 
-                    egui::ComboBox::from_id_salt("Accounts")
+                    let current = self.steam_model.user_cache.iter().find(|x| x.name == self.selected_account).cloned();
+                    let mut selected_account = self.selected_account.clone();
+                    let response = egui::ComboBox::from_id_salt("Accounts")
                         .width(ui.available_width())
-                        .selected_text(format!("{}", self.selected_account))
+                        .selected_text(match &current {
+                            Some(account) => self.account_rich_text(account),
+                            None => egui::RichText::new(&self.selected_account),
+                        })
                         .show_ui(ui, |ui| {
-                            for steam_account in &self.steam_model.user_cache {
-                                ui.selectable_value(
-                                    &mut self.selected_account,
+                            for steam_account in self.visible_accounts() {
+                                account_option(
+                                    ui,
+                                    &mut selected_account,
                                     steam_account.name().to_string(),
-                                    steam_account.name(),
+                                    steam_account,
+                                    self.account_rich_text(steam_account),
                                 );
                             }
+                        })
+                        .response;
+                    self.selected_account = selected_account;
+                    if let Some(account) = &current {
+                        account_context_menu(&response, account);
+                    }
+                    if current.is_some_and(|x| !x.can_auto_login()) {
+                        ui.colored_label(NO_AUTO_LOGIN_COLOR, "⚠ Steam will ask for the password of this account");
+                    }
+
+                    let mut mode = self.launch_config.start_mode(&self.selected_account);
+                    egui::ComboBox::from_id_salt("Start Mode")
+                        .width(ui.available_width())
+                        .selected_text(format!("Start {}", mode))
+                        .show_ui(ui, |ui| {
+                            for option in StartMode::ALL {
+                                ui.selectable_value(&mut mode, option, option.to_string());
+                            }
                         });
+                    if mode != self.launch_config.start_mode(&self.selected_account) {
+                        match mode {
+                            StartMode::Online => self.launch_config.start_modes.remove(&self.selected_account),
+                            _ => self.launch_config.start_modes.insert(self.selected_account.clone(), mode),
+                        };
+                        self.needs_save = true;
+                    }
                     
+                    if self.steam_model.dry_run {
+                        ui.colored_label(Color32::from_rgb(200, 150, 40), "Dry run, nothing will be changed")
+                            .on_hover_text("Logins and launches only show what they would do, turn this off in the settings");
+                    }
+
                     if ui.button("Login to Steam").clicked() {
-                        self.toasts.info(format!("Logging in as {}", self.selected_account));
-                        match self.steam_model.login(
-                            &self.selected_account,
-                            self.close_after == CloseAfter::Login || self.close_after == CloseAfter::Both
-                        ) {
-                            Ok(_) => {},
-                            Err(e) => {
-                                log::error!("Login Error: {}", e);
-                            }
+                        self.login(self.selected_account.clone());
+                    }
+
+                    if let Some(task) = &self.launch_task {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            match &task.stage {
+                                Some(stage) => ui.label(stage.to_string()),
+                                None => ui.label("Preparing"),
+                            };
+                        });
+                        if ui.button("Cancel").clicked() {
+                            task.cancel();
                         }
                     }
+                    for watch in &self.exit_watches {
+                        ui.horizontal(|ui| {
+                            let stage = watch.stage.as_ref().map(|x| x.to_string()).unwrap_or("Waiting for the game".to_string());
+                            let text = match &watch.restore {
+                                Some(restore) => format!("↩ {}", restore),
+                                None => "Post-exit hooks".to_string(),
+                            };
+                            ui.label(text)
+                                .on_hover_text(format!("Waiting for the game to exit\n{}", stage));
+                            if ui.small_button("Cancel").clicked() {
+                                watch.cancel();
+                            }
+                        });
+                    }
 
                     ui.separator();
 
-                    if let Some(app) = &self.selected_app {
+                    if let Some(app) = &self.selected_app.clone() {
                         ui.heading(app.name.to_string());
+                        if self.running.contains(&app.id) {
+                            ui.colored_label(Color32::from_rgb(90, 170, 90), "Running");
+                        }
+                        self.install_state_ui(ui, app);
 
                         let thumbnail: Thumbnail = self.thumbnail_cache.get(&app).unwrap_or(&Thumbnail::default()).clone();
                         if let Some(portrait) = thumbnail.portrait {
@@ -299,38 +529,16 @@ impl eframe::App for App {
                             Vec2::new(ui.available_width(), 60.0),
                             Layout::top_down_justified(Align::Center),
                             |ui| {
-                                if self.saved_logins.get(app).is_none() {
+                                if !self.saved_logins.contains_key(app) && self.assign_policy != AssignPolicy::Ask {
                                     self.saved_logins.insert(app.clone(), self.selected_account.clone());
                                 }
 
-                                egui::ComboBox::from_id_salt("Game Account")
-                                    .width(ui.available_width())
-                                    .selected_text(format!("{}", self.saved_logins.get(app).expect("Game account was not properly initialized!")))
-                                    .show_ui(ui, |ui| {
-                                        for steam_account in &self.steam_model.user_cache {
-                                            if ui.selectable_value(
-                                                &mut *self.saved_logins.get_mut(app).unwrap(),
-                                                steam_account.name.clone(),
-                                                steam_account.name(),
-                                            ).clicked() {
-                                                self.toasts.info(format!("Updated game \"{}\" to account \"{}\"", app.name, steam_account.name()));
-                                                self.needs_save = true;
-                                            }
-                                        }
-                                    });
+                                self.account_picker(ui, app, "Game Account");
 
 
                                 ui.horizontal(|ui| {
                                     let width = ui.available_width() / 2.0 - ui.spacing().item_spacing.x / 2.0;
-                                    if ui.add_sized(Vec2::new(width, 40.0), egui::Button::new("Launch")).clicked() {
-                                        self.toasts.info(format!("Launching {}", app.name));
-                                        steam_launch!(
-                                            self.steam_model,
-                                            self.saved_logins.get(app).expect("Game account was not properly initialized!"),
-                                            app,
-                                            self.close_after == CloseAfter::Launch || self.close_after == CloseAfter::Both
-                                        );
-                                    }
+                                    self.launch_button(ui, app, Vec2::new(width, 40.0));
                                     if ui.add_sized(Vec2::new(width, 40.0), egui::Button::new("SteamDB")).clicked() {
                                         let url = format!("https://steamdb.info/app/{}", app.id);
                                         open::that(url).unwrap();
@@ -339,6 +547,28 @@ impl eframe::App for App {
                             },
                         );
 
+                        let mut restore = self.restore_all || self.restore_games.contains(app);
+                        if ui.add_enabled(!self.restore_all, egui::Checkbox::new(&mut restore, "Switch back account after exit")).changed() {
+                            if restore {
+                                self.restore_games.insert(app.clone());
+                            } else {
+                                self.restore_games.remove(app);
+                            }
+                            self.needs_save = true;
+                        }
+
+                        self.launch_profiles_section(ui, app);
+                        self.steam_launch_options_section(ui, app);
+                        self.game_hooks_section(ui, app);
+
+                        let owners = self.visible_owners(&app.id);
+                        if !owners.is_empty() {
+                            ui.separator();
+                            for account in owners {
+                                ui.label(format!("{}: {}", self.account_name(account), format_stats(account, &app.id)));
+                            }
+                        }
+
                         egui::Frame::default()
                             .show(ui, |ui| {
                                 egui::ScrollArea::vertical()
@@ -361,108 +591,549 @@ impl eframe::App for App {
         egui::CentralPanel::default()
             .frame(egui::Frame::default().fill(self.theme.background).inner_margin(egui::Margin::same(8.0)))
             .show(ctx, |ui| {
-                ui.vertical(|ui| {
-                    egui::ScrollArea::horizontal()
-                        .id_salt("top_bar")
-                        .scroll_bar_visibility(egui::containers::scroll_area::ScrollBarVisibility::AlwaysHidden)
-                        .hscroll(true)
-                        .drag_to_scroll(true)
-                        .show(ui, |ui| {
-                            ui.horizontal(|ui| {
-                                ui.heading("Steam Library");
-        
-                                egui::ComboBox::from_label("Sort Mode")
-                                    .selected_text(format!("{}", self.sort_mode))
-                                    .show_ui(ui, |ui| {
-                                        ui.selectable_value(&mut self.sort_mode, SortMode::LastPlayed, "Last Played");
-                                        ui.selectable_value(&mut self.sort_mode, SortMode::Name, "Name");
-                                    });
-        
-                                ui.radio_value(&mut self.thumbnail_mode, ThumbnailMode::Portrait, "Portrait");
-                                ui.radio_value(&mut self.thumbnail_mode, ThumbnailMode::Landscape, "Landscape");
-                                ui.add(
-                                    egui::Slider::new(&mut self.grid_size, 30.0..=400.0)
-                                        .text("Grid Size")
-                                        .step_by(10.0)
-                                        .clamping(egui::SliderClamping::Always)
-                                );
-                                ui.add(
-                                    egui::TextEdit::singleline(&mut self.search_filter)
-                                        .hint_text("Search")
-                                        .desired_width(300.0)
-                                );
-        
-                                egui::ComboBox::from_label("Close After")
-                                    .selected_text(format!("{}", self.close_after))
-                                    .show_ui(ui, |ui| {
-                                        ui.selectable_value(&mut self.close_after, CloseAfter::None, "None");
-                                        ui.selectable_value(&mut self.close_after, CloseAfter::Launch, "Launch");
-                                        ui.selectable_value(&mut self.close_after, CloseAfter::Login, "Login");
-                                        ui.selectable_value(&mut self.close_after, CloseAfter::Both, "Both");
-                                    });
-        
-                                let response = ui.button("Theme");
-                                if response.clicked() {
-                                    self.theme_popup = !self.theme_popup;
-                                };
-                                if self.theme_popup {
-                                    theme_popup(ui, &response, |ui| {
-                                        ui.color_edit_button_srgba(&mut self.theme.primary).on_hover_text("Primary");
-                                        ui.color_edit_button_srgba(&mut self.theme.secondary).on_hover_text("Secondary");
-                                        ui.color_edit_button_srgba(&mut self.theme.background).on_hover_text("Background");
-                                        ui.color_edit_button_srgba(&mut self.theme.text).on_hover_text("Text");
-        
-                                        if ui.button("Reset").clicked() {
-                                            self.theme = Theme::default();
-                                        }
-                                    });
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.view, View::Library, "Library");
+                    ui.selectable_value(&mut self.view, View::History, "History");
+                    ui.selectable_value(&mut self.view, View::Conflicts, "Multiple Owners");
+                    ui.selectable_value(&mut self.view, View::Accounts, "Accounts");
+                    ui.selectable_value(&mut self.view, View::Storage, "Storage");
+                    ui.selectable_value(&mut self.view, View::Restore, "Restore");
+                    let mismatches = self.mismatches().len();
+                    if mismatches > 0 {
+                        ui.selectable_value(
+                            &mut self.view,
+                            View::Ownership,
+                            egui::RichText::new(format!("⚠ Mismatches ({})", mismatches)).color(NOT_OWNED_COLOR),
+                        );
+                    } else {
+                        ui.selectable_value(&mut self.view, View::Ownership, "Mismatches");
+                    }
+                });
+                ui.separator();
+
+                match self.view {
+                    View::Library => self.library_view(ui),
+                    View::History => self.history_view(ui),
+                    View::Ownership => self.ownership_view(ui),
+                    View::Conflicts => self.conflicts_view(ui),
+                    View::Accounts => self.accounts_view(ui),
+                    View::Storage => self.storage_view(ui),
+                    View::Restore => self.restore_view(ui),
+                }
+            }
+        );
+
+        self.settings_window(ctx);
+        self.confirm_window(ctx);
+        self.forget_window(ctx);
+        self.dry_run_window(ctx);
+
+        // Trigger save if needed
+        if self.needs_save {
+            if let Some(storage) = frame.storage_mut() {
+                self.save(storage);
+                self.needs_save = false;
+            }
+        }
+    }
+}
+
+impl App {
+    /// Launch a game with its saved account, or with one of its launch profiles
+    /// 
+    /// Running games are brought to the front instead, and restarting Steam while a game is running needs confirmation
+    fn launch_app(&mut self, app: &AppID, profile: Option<String>) {
+        if self.running.contains(&app.id) {
+            self.toasts.info(format!("{} is already running", app.name));
+            match self.steam_model.focus_game(&app.id) {
+                Ok(Some(step)) => {
+                    log::info!("Dry run: {}", step);
+                    self.dry_run_log.push(step);
+                    self.dry_run_log.push(String::new());
+                },
+                Ok(None) => {},
+                Err(e) => log::error!("Focus Error: {}", e),
+            }
+            return;
+        }
+
+        if let Some(name) = &profile {
+            if self.launch_profile(app, name).is_none() {
+                self.toasts.error(format!("{} has no launch profile named {}", app.name, name));
+                return;
+            }
+        }
+        let Some(account) = self.launch_account(app, profile.as_deref()) else {
+            self.toasts.warning(format!("Choose an account for {} first", app.name));
+            self.selected_app = Some(app.clone());
+            return;
+        };
+        let env = profile.as_deref().and_then(|name| self.launch_profile(app, name)).map(|x| x.env()).unwrap_or_default();
+        if self.confirm_restart(&account, &env) {
+            self.pending_action = Some(PendingAction::Launch(app.clone(), profile));
+            return;
+        }
+
+        self.start_launch(app, profile.as_deref());
+    }
+
+    /// Get the account a game launches with, the profile's account takes precedence over the saved account
+    fn launch_account(&self, app: &AppID, profile: Option<&str>) -> Option<String> {
+        profile
+            .and_then(|name| self.launch_profile(app, name))
+            .and_then(|x| x.account.clone())
+            .or_else(|| self.saved_logins.get(app).cloned())
+    }
+
+    /// Check whether starting a pipeline for the account has to be confirmed first, because it would close Steam while a game is running
+    fn confirm_restart(&self, account: &str, env: &[(String, String)]) -> bool {
+        !self.running.is_empty() && self.steam_model.restarts_steam(account, self.launch_config.start_mode(account), env)
+    }
+
+    /// Login with an account, asking for confirmation first if a game is running
+    fn login(&mut self, account: String) {
+        if self.confirm_restart(&account, &[]) {
+            self.pending_action = Some(PendingAction::Login(account));
+            return;
+        }
+
+        self.start_login(account);
+    }
+
+    /// Show the steps of the last dry runs
+    fn dry_run_window(&mut self, ctx: &egui::Context) {
+        if self.dry_run_log.is_empty() {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Dry Run")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                ui.label("These steps would have been taken:");
+                egui::ScrollArea::vertical()
+                    .id_salt("dry_run_log")
+                    .max_height(300.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for step in &self.dry_run_log {
+                            if step.is_empty() {
+                                ui.separator();
+                            } else {
+                                ui.label(format!("• {}", step));
+                            }
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    if ui.button("Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = self.dry_run_log.join("\n"));
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.dry_run_log.clear();
+                    }
+                });
+            });
+
+        if !open {
+            self.dry_run_log.clear();
+        }
+    }
+
+    /// Show the confirmation window for a pending action
+    fn confirm_window(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.pending_action.clone() else {
+            return;
+        };
+        let running: Vec<String> = self.steam_model
+            .get_installed_apps()
+            .into_iter()
+            .filter(|app| self.running.contains(&app.id))
+            .map(|app| app.name)
+            .collect();
+
+        egui::Window::new("Steam Restart")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("Steam has to restart, which will close {}.", running.join(", ")));
+                ui.label("Continue anyway?");
+                ui.horizontal(|ui| {
+                    if ui.button("Continue").clicked() {
+                        self.pending_action = None;
+                        match &action {
+                            PendingAction::Launch(app, profile) => self.start_launch(app, profile.as_deref()),
+                            PendingAction::Login(account) => self.start_login(account.clone()),
+                            PendingAction::Install(appid, account) => self.start_install(*appid, account.clone()),
+                            PendingAction::Action(app, action) => self.start_client_action(app, *action),
+                        }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending_action = None;
+                    }
+                });
+            });
+    }
+
+    /// Start the launch pipeline for a game with its saved account, or with one of its launch profiles
+    fn start_launch(&mut self, app: &AppID, profile: Option<&str>) {
+        if self.launch_task.is_some() {
+            self.toasts.warning("A launch is already in progress");
+            return;
+        }
+
+        let Some(account) = self.launch_account(app, profile) else {
+            return;
+        };
+        let profile = profile.and_then(|name| self.launch_profile(app, name)).cloned();
+        let restore = if self.restore_all || self.restore_games.contains(app) {
+            match &self.home_account {
+                Some(home) => Some(home.clone()),
+                None => self.steam_model.get_current_user().ok().map(|user| user.name),
+            }
+        } else {
+            None
+        };
+
+        match self.steam_model.launch_game(&account, &app.id, restore, profile.as_ref(), &self.launch_config) {
+            Ok(task) => {
+                match profile {
+                    Some(profile) => self.toasts.info(format!("Launching {} ({})", app.name, profile.name)),
+                    None => self.toasts.info(format!("Launching {}", app.name)),
+                };
+                self.launch_task = Some(task);
+            },
+            Err(e) => {
+                log::error!("Launch Error: {}", e);
+                self.toasts.error(format!("Launch Error: {}", e));
+            }
+        }
+    }
+
+    /// Start the launch pipeline to login with an account
+    fn start_login(&mut self, account: String) {
+        if self.launch_task.is_some() {
+            self.toasts.warning("A launch is already in progress");
+            return;
+        }
+
+        match self.steam_model.login(&account, &self.launch_config) {
+            Ok(task) => {
+                self.toasts.info(format!("Logging in as {}", account));
+                self.launch_task = Some(task);
+            },
+            Err(e) => {
+                log::error!("Login Error: {}", e);
+                self.toasts.error(format!("Login Error: {}", e));
+            }
+        }
+    }
+
+    /// Handle events sent by the running launch pipeline
+    fn poll_launch_task(&mut self, ctx: &egui::Context) {
+        let Some(task) = &mut self.launch_task else {
+            return;
+        };
+        let events = task.poll();
+        let account = task.account.clone();
+        let restore = task.restore.clone();
+        let appid = task.appid;
+        let dry_run = task.dry_run;
+        let is_launch = appid.is_some();
+        // Installs and other client actions only hand a url to Steam
+        let action = task.url.clone().filter(|_| !is_launch);
+
+        // Keep polling while the pipeline is running
+        ctx.request_repaint_after(Duration::from_millis(250));
+
+        for event in events {
+            match event {
+                LaunchEvent::Stage(LaunchStage::GameStarted) => {
+                    let app = self.steam_model
+                        .get_installed_apps()
+                        .into_iter()
+                        .find(|app| Some(app.id) == appid);
+                    if let Some(app) = app.filter(|_| !dry_run) {
+                        self.history.expect(app, account.clone());
+                    }
+                    let post_exit = appid.is_some_and(|x| !self.launch_config.hook_commands(x, HookKind::PostExit).is_empty());
+                    if restore.is_some() || post_exit {
+                        let request = LaunchRequest {
+                            account: account.clone(),
+                            appid,
+                            restore: restore.clone(),
+                            ..Default::default()
+                        };
+                        self.exit_watches.push(self.steam_model.watch_exit(request, &self.launch_config));
+                    }
+                },
+                LaunchEvent::Stage(stage) => {
+                    log::info!("{}: {}", account, stage);
+                },
+                LaunchEvent::Warning(warning) => {
+                    self.toasts.warning(warning);
+                },
+                LaunchEvent::DryRun(step) => {
+                    self.dry_run_log.push(step);
+                },
+                LaunchEvent::Finished if dry_run => {
+                    self.launch_task = None;
+                    self.dry_run_log.push(String::new());
+                    self.toasts.info("Dry run finished, nothing was changed");
+                },
+                LaunchEvent::Finished => {
+                    self.launch_task = None;
+                    if let Some(url) = &action {
+                        self.toasts.success(format!("Sent {} to Steam as {}", url, account));
+                        continue;
+                    }
+                    match &restore {
+                        Some(restore) => self.toasts.success(format!("Steam started as {}, switching back to {} once the game exits", account, restore)),
+                        None => self.toasts.success(format!("Steam started as {}", account)),
+                    };
+
+                    let close = match self.close_after {
+                        CloseAfter::Both => true,
+                        CloseAfter::Launch => is_launch,
+                        CloseAfter::Login => !is_launch,
+                        CloseAfter::None => false,
+                    };
+                    if close {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                },
+                LaunchEvent::Failed(e) => {
+                    self.launch_task = None;
+                    self.toasts.error(format!("Launch failed: {}", e));
+                },
+                LaunchEvent::Cancelled => {
+                    self.launch_task = None;
+                    self.toasts.info("Launch cancelled");
+                },
+            }
+        }
+    }
+
+    /// Handle events sent by the exit watches, which switch back accounts once their game exits
+    fn poll_exit_watches(&mut self) {
+        let mut watches = std::mem::take(&mut self.exit_watches);
+        watches.retain_mut(|watch| {
+            let restore = watch.restore.clone();
+            for event in watch.poll() {
+                match event {
+                    LaunchEvent::Stage(stage) => log::info!("Exit watch of {:?}: {}", watch.appid, stage),
+                    LaunchEvent::Warning(warning) => {
+                        self.toasts.warning(warning);
+                    },
+                    LaunchEvent::DryRun(step) => self.dry_run_log.push(step),
+                    LaunchEvent::Finished if watch.dry_run => {
+                        self.dry_run_log.push(String::new());
+                        return false;
+                    },
+                    LaunchEvent::Finished => {
+                        if let Some(restore) = &restore {
+                            self.toasts.success(format!("Switched back to {}", restore));
+                        }
+                        return false;
+                    },
+                    LaunchEvent::Failed(e) => {
+                        self.toasts.error(format!("After the game exited: {}", e));
+                        return false;
+                    },
+                    LaunchEvent::Cancelled => {
+                        self.toasts.info("Stopped waiting for the game to exit");
+                        return false;
+                    },
+                }
+            }
+            true
+        });
+        self.exit_watches = watches;
+    }
+
+    /// Show the library top bar and game grid
+    fn library_view(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            egui::ScrollArea::horizontal()
+                .id_salt("top_bar")
+                .scroll_bar_visibility(egui::containers::scroll_area::ScrollBarVisibility::AlwaysHidden)
+                .hscroll(true)
+                .drag_to_scroll(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.heading("Steam Library");
+
+                        egui::ComboBox::from_label("Sort Mode")
+                            .selected_text(format!("{}", self.sort_mode))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.sort_mode, SortMode::LastPlayed, "Last Played");
+                                ui.selectable_value(&mut self.sort_mode, SortMode::Name, "Name");
+                                ui.selectable_value(&mut self.sort_mode, SortMode::Playtime, "Play Time");
+                            });
+
+                        ui.radio_value(&mut self.thumbnail_mode, ThumbnailMode::Portrait, "Portrait");
+                        ui.radio_value(&mut self.thumbnail_mode, ThumbnailMode::Landscape, "Landscape");
+                        ui.add(
+                            egui::Slider::new(&mut self.grid_size, 30.0..=400.0)
+                                .text("Grid Size")
+                                .step_by(10.0)
+                                .clamping(egui::SliderClamping::Always)
+                        );
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.search_filter)
+                                .hint_text("Search")
+                                .desired_width(300.0)
+                        );
+                        ui.checkbox(&mut self.needs_update_filter, "Needs update");
+
+                        let mut folders: Vec<PathBuf> = self.steam_model.directories.keys().cloned().collect();
+                        folders.sort();
+                        egui::ComboBox::from_label("Library")
+                            .selected_text(match &self.library_filter {
+                                Some(folder) => library_label(&self.disks, folder),
+                                None => "All folders".to_string(),
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.library_filter, None, "All folders");
+                                for folder in folders {
+                                    let label = library_label(&self.disks, &folder);
+                                    ui.selectable_value(&mut self.library_filter, Some(folder), label);
                                 }
                             });
-                        });
+                        ui.checkbox(&mut self.group_by_folder, "Group by folder");
+
+                        egui::ComboBox::from_label("Close After")
+                            .selected_text(format!("{}", self.close_after))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.close_after, CloseAfter::None, "None");
+                                ui.selectable_value(&mut self.close_after, CloseAfter::Launch, "Launch");
+                                ui.selectable_value(&mut self.close_after, CloseAfter::Login, "Login");
+                                ui.selectable_value(&mut self.close_after, CloseAfter::Both, "Both");
+                            });
+
+                        if ui.button("Settings").clicked() {
+                            self.settings_open = !self.settings_open;
+                        }
+
+                        let response = ui.button("Theme");
+                        if response.clicked() {
+                            self.theme_popup = !self.theme_popup;
+                        };
+                        if self.theme_popup {
+                            theme_popup(ui, &response, |ui| {
+                                ui.color_edit_button_srgba(&mut self.theme.primary).on_hover_text("Primary");
+                                ui.color_edit_button_srgba(&mut self.theme.secondary).on_hover_text("Secondary");
+                                ui.color_edit_button_srgba(&mut self.theme.background).on_hover_text("Background");
+                                ui.color_edit_button_srgba(&mut self.theme.text).on_hover_text("Text");
+
+                                if ui.button("Reset").clicked() {
+                                    self.theme = Theme::default();
+                                }
+                            });
+                        }
+                    });
+                });
 
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .id_salt("game_grid")
+                .show(ui, |ui| {
+                    egui::CollapsingHeader::new(format!("Favorites ({})", self.favorites.len()))
+                        .default_open(true)
+                        .show(ui, |ui| self.game_grid(ui, self.sort_apps(self.favorites.clone()))
+                    );
+                    
                     ui.separator();
 
-                    egui::ScrollArea::vertical()
-                        .id_salt("game_grid")
-                        .show(ui, |ui| {
-                            egui::CollapsingHeader::new(format!("Favorites ({})", self.favorites.len()))
-                                .default_open(true)
-                                .show(ui, |ui| self.game_grid(ui, self.sort_apps(self.favorites.clone()))
-                            );
-                            
-                            ui.separator();
+                    let recent: Vec<AppID> = self.history
+                        .recent(10)
+                        .into_iter()
+                        .filter_map(|app| self.steam_model.games.keys().find(|x| x.id == app.id).cloned())
+                        .filter(|app| !self.hidden.contains(app))
+                        .collect();
+                    if !recent.is_empty() {
+                        egui::CollapsingHeader::new(format!("Recently Launched via wait ({})", recent.len()))
+                            .default_open(true)
+                            .show(ui, |ui| self.game_grid(ui, recent)
+                        );
 
-                            egui::CollapsingHeader::new(format!("All Games ({})", self.steam_model.games.len()))
+                        ui.separator();
+                    }
+
+                    if self.group_by_folder {
+                        let mut folders: Vec<PathBuf> = self.steam_model.directories.keys().cloned().collect();
+                        folders.sort();
+                        for folder in folders {
+                            let apps = self.sort_apps(
+                                self.steam_model
+                                    .get_installed_apps()
+                                    .into_iter()
+                                    .filter(|app| is_in_folder(app, &folder))
+                            );
+                            if apps.is_empty() {
+                                continue;
+                            }
+                            egui::CollapsingHeader::new(format!("{} - {} games", library_label(&self.disks, &folder), apps.len()))
+                                .id_salt(&folder)
                                 .default_open(true)
-                                .show(ui, |ui| self.game_grid(ui, self.sort_apps(self.steam_model.get_installed_apps()))
+                                .show(ui, |ui| self.game_grid(ui, apps)
                             );
+                        }
+                    } else {
+                        let title = match &self.library_filter {
+                            Some(folder) => format!("All Games in {} ({})", library_label(&self.disks, folder), self.sort_apps(self.steam_model.get_installed_apps()).len()),
+                            None => format!("All Games ({})", self.steam_model.games.len()),
+                        };
+                        egui::CollapsingHeader::new(title)
+                            .id_salt("all_games")
+                            .default_open(true)
+                            .show(ui, |ui| self.game_grid(ui, self.sort_apps(self.steam_model.get_installed_apps()))
+                        );
+                    }
 
-                            ui.separator();
+                    ui.separator();
 
-                            egui::CollapsingHeader::new(format!("Hidden ({})", self.hidden.len()))
-                                .default_open(true)
-                                .show(ui, |ui| {
-                                    if self.hidden.len() > 0 {
-                                        self.game_grid(ui, self.hidden.clone());
-                                    }
-                                }
-                            );
+                    self.not_installed_section(ui);
+
+                    ui.separator();
+
+                    egui::CollapsingHeader::new(format!("Hidden ({})", self.hidden.len()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            if self.hidden.len() > 0 {
+                                self.game_grid(ui, self.hidden.clone());
+                            }
                         }
                     );
-                });
-            }
-        );
+                }
+            );
+        });
+    }
 
-        // Trigger save if needed
-        if self.needs_save {
-            if let Some(storage) = frame.storage_mut() {
-                self.save(storage);
+    /// Get the badges shown on a game's grid card
+    fn card_badges(&self, app: &AppID) -> Vec<(String, Color32)> {
+        let mut badges = Vec::new();
+        if self.running.contains(&app.id) {
+            badges.push(("Running".to_string(), Color32::from_rgb(60, 140, 60)));
+        }
+        if self.is_mismatched(app) {
+            badges.push(("Not owned".to_string(), NOT_OWNED_COLOR));
+        }
+        let status = self.steam_model.install_state(app).status();
+        if let Some(color) = status_color(status) {
+            badges.push((status.to_string(), color));
+        }
+        if let Some(account) = self.saved_logins.get(app) {
+            if let (Some(color), Some(steam_account)) = (self.account_color(account), self.steam_model.user_cache.iter().find(|x| x.name() == account)) {
+                badges.push((self.account_name(steam_account).to_string(), color));
             }
         }
+        badges
     }
-}
 
-impl App {
     fn get_thumbnail_image(&'_ self, app: &AppID) -> egui::Image<'_> {
         let thumbnail: Thumbnail = self.thumbnail_cache.get(&app).unwrap_or(&Thumbnail::default()).clone();
 
@@ -484,11 +1155,20 @@ impl App {
         }
     }
 
+    /// Get the minutes played of a game across all accounts
+    fn playtime(&self, appid: &i32) -> u64 {
+        self.steam_model.user_cache.iter().map(|x| x.playtime(appid)).sum()
+    }
+
     fn sort_apps<T>(&self, apps: T) -> Vec<AppID>
         where T: IntoIterator<Item = AppID>
     {
         let mut apps = apps.into_iter().filter_map(|app| {
-            if !self.hidden.contains(&app) {
+            let matches_update = !self.needs_update_filter || self.steam_model.install_state(&app).needs_update();
+            let matches_folder = self.library_filter.as_ref().is_none_or(|folder| is_in_folder(&app, folder));
+            if !matches_update || !matches_folder {
+                None
+            } else if !self.hidden.contains(&app) {
                 if self.search_filter.is_empty() {
                     Some(app.clone())
                 } else if app.name.to_lowercase().contains(&self.search_filter) {
@@ -508,6 +1188,9 @@ impl App {
                 },
                 SortMode::Name => {
                     a.name.cmp(&b.name)
+                },
+                SortMode::Playtime => {
+                    self.playtime(&b.id).cmp(&self.playtime(&a.id))
                 }
             }
         });
@@ -553,6 +1236,7 @@ impl App {
                                     .rounding(8.5)
                                     .sense(egui::Sense::click())
                             );
+                            badges(ui, response.rect, &self.card_badges(&app));
         
                             self.game_context(&response, &app);
                             
@@ -570,21 +1254,34 @@ impl App {
     fn game_context(&mut self, response: &egui::Response, app: &AppID) {
         response.context_menu(|ui| {
             if ui.button("Launch").clicked() {
-                self.toasts.info(format!("Launching {}", app.name));
-                steam_launch!(
-                    self.steam_model,
-                    self.saved_logins.get(app).expect("Game account was not properly initialized!"),
-                    app,
-                    self.close_after == CloseAfter::Launch || self.close_after == CloseAfter::Both
-                );
+                self.launch_app(app, None);
                 ui.close_menu();
             }
+            if let Some(profiles) = self.launch_profiles.get(app).filter(|x| !x.is_empty()) {
+                let names: Vec<String> = profiles.iter().map(|x| x.name.clone()).collect();
+                ui.menu_button("Launch Profile", |ui| {
+                    for name in names {
+                        if ui.button(&name).clicked() {
+                            self.launch_app(app, Some(name));
+                            ui.close_menu();
+                        }
+                    }
+                });
+            }
             if ui.button("Open Location").clicked() {
                 let path = app.location.clone();
                 let path_str = path.to_str().expect("Failed to convert path to string");
                 open::that(path_str).unwrap();
                 ui.close_menu();
             }
+            ui.menu_button("Steam", |ui| {
+                for action in ClientAction::ALL {
+                    if ui.button(action.to_string()).clicked() {
+                        self.client_action(app, action);
+                        ui.close_menu();
+                    }
+                }
+            });
             if self.favorites.contains(&app) {
                 if ui.button("Remove from Favorites").clicked() {
                     self.favorites.retain(|x| x != app);
@@ -613,4 +1310,9 @@ impl App {
             }
         });
     }
-}
\ No newline at end of file
+}
+
+/// Check whether a game is installed in a library folder
+fn is_in_folder(app: &AppID, folder: &std::path::Path) -> bool {
+    steam::normalize_path(&app.location).starts_with(&steam::normalize_path(folder))
+}
@@ -0,0 +1,256 @@
+use std::{collections::{HashMap, HashSet}, hash::Hash};
+
+use egui::Color32;
+
+use super::accounts::{account_option, NO_AUTO_LOGIN_COLOR};
+use super::format::format_stats;
+use super::steam::prelude::*;
+use super::App;
+
+/// Color used to warn about accounts that don't own a game
+pub const NOT_OWNED_COLOR: Color32 = Color32::from_rgb(200, 120, 40);
+
+/// Ownership
+///
+/// The owners of every installed game and the games whose saved account doesn't own them.
+/// Computed once per frame by [`App::refresh_ownership`], as the game grid asks for every card
+#[derive(Debug, Default, Clone)]
+pub(super) struct Ownership {
+    /// Positions in `user_cache` of the accounts owning each game, in account priority order
+    owners: HashMap<i32, Vec<usize>>,
+    mismatched: HashSet<i32>,
+}
+
+impl App {
+    /// Compute the owners of all installed games and which saved accounts don't own their game
+    pub(super) fn refresh_ownership(&mut self) {
+        let accounts = &self.steam_model.user_cache;
+        let mut ordered: Vec<usize> = (0..accounts.len()).collect();
+        ordered.sort_by_key(|i| {
+            self.account_order
+                .iter()
+                .position(|name| name == accounts[*i].name())
+                .unwrap_or(usize::MAX)
+        });
+
+        let owners: HashMap<i32, Vec<usize>> = self.steam_model.games
+            .keys()
+            .map(|app| (app.id, ordered.iter().copied().filter(|i| accounts[*i].games.contains(&app.id)).collect()))
+            .collect();
+        let mismatched = self.saved_logins
+            .iter()
+            .filter(|(app, account)| owners.get(&app.id).is_some_and(|x| !x.iter().any(|i| accounts[*i].name() == *account)))
+            .map(|(app, _)| app.id)
+            .collect();
+
+        self.ownership = Ownership { owners, mismatched };
+    }
+
+    /// Get all accounts that own a game, in account priority order
+    pub(super) fn owners(&self, appid: &i32) -> Vec<&SteamAccount> {
+        match self.ownership.owners.get(appid) {
+            Some(owners) => owners.iter().filter_map(|i| self.steam_model.user_cache.get(*i)).collect(),
+            None => self.ordered_accounts()
+                .into_iter()
+                .filter(|x| x.games.contains(appid))
+                .collect(),
+        }
+    }
+
+    /// Get the accounts that own a game and aren't hidden, in account priority order
+    pub(super) fn visible_owners(&self, appid: &i32) -> Vec<&SteamAccount> {
+        self.owners(appid)
+            .into_iter()
+            .filter(|x| !self.is_account_hidden(x.name()))
+            .collect()
+    }
+
+    /// Check whether the saved account of a game doesn't appear to own it
+    pub(super) fn is_mismatched(&self, app: &AppID) -> bool {
+        self.ownership.mismatched.contains(&app.id)
+    }
+
+    /// Get all installed games whose saved account doesn't appear to own them
+    pub(super) fn mismatches(&self) -> Vec<AppID> {
+        let mut apps: Vec<AppID> = self.steam_model.games
+            .keys()
+            .filter(|app| self.is_mismatched(app))
+            .cloned()
+            .collect();
+        apps.sort_by(|a, b| a.name.cmp(&b.name));
+        apps
+    }
+
+    /// Get all installed games owned by two or more accounts
+    pub(super) fn conflicts(&self) -> Vec<AppID> {
+        let mut apps: Vec<AppID> = self.steam_model
+            .get_installed_apps()
+            .into_iter()
+            .filter(|app| self.visible_owners(&app.id).len() > 1)
+            .collect();
+        apps.sort_by(|a, b| a.name.cmp(&b.name));
+        apps
+    }
+
+    /// Save the account a game launches with as a manual choice, which re-evaluating logins keeps
+    /// 
+    /// Does nothing if the game already uses the account
+    pub(super) fn set_game_account(&mut self, app: &AppID, account: String) {
+        if self.saved_logins.get(app) == Some(&account) {
+            return;
+        }
+        self.toasts.info(format!("Updated game \"{}\" to account \"{}\"", app.name, account));
+        self.saved_logins.insert(app.clone(), account);
+        self.manual_logins.insert(app.clone());
+        self.needs_save = true;
+        self.refresh_ownership();
+    }
+
+    /// Show a combo box to choose the account of a game
+    /// 
+    /// Owning accounts are listed first with their play time, followed by the remaining accounts
+    pub(super) fn account_picker(&mut self, ui: &mut egui::Ui, app: &AppID, id_salt: impl Hash) {
+        let owners: Vec<SteamAccount> = self.visible_owners(&app.id).into_iter().cloned().collect();
+        let others: Vec<SteamAccount> = self.visible_accounts()
+            .into_iter()
+            .filter(|x| !owners.contains(x))
+            .cloned()
+            .collect();
+
+        let mut game_account = self.saved_logins.get(app).cloned();
+        let selected_text = match &game_account {
+            Some(account) if self.is_mismatched(app) => egui::RichText::new(format!("⚠ {}", account)).color(NOT_OWNED_COLOR),
+            Some(account) => egui::RichText::new(account),
+            None => egui::RichText::new("Choose an account"),
+        };
+
+        egui::ComboBox::from_id_salt(id_salt)
+            .width(ui.available_width())
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                if !owners.is_empty() {
+                    ui.weak("Owns this game");
+                }
+                for steam_account in &owners {
+                    let mut text = egui::RichText::new(format!("{} ({})", self.account_text(steam_account), format_stats(steam_account, &app.id)));
+                    if !steam_account.can_auto_login() {
+                        text = text.color(NO_AUTO_LOGIN_COLOR);
+                    }
+                    account_option(
+                        ui,
+                        &mut game_account,
+                        Some(steam_account.name().to_string()),
+                        steam_account,
+                        text,
+                    );
+                }
+
+                if !others.is_empty() {
+                    ui.separator();
+                    ui.weak("Doesn't appear to own this game");
+                }
+                for steam_account in &others {
+                    account_option(
+                        ui,
+                        &mut game_account,
+                        Some(steam_account.name().to_string()),
+                        steam_account,
+                        egui::RichText::new(self.account_text(steam_account)).color(NOT_OWNED_COLOR),
+                    );
+                }
+            });
+
+        if let Some(game_account) = game_account {
+            self.set_game_account(app, game_account);
+        }
+    }
+
+    /// Show all games whose saved account doesn't appear to own them
+    pub(super) fn ownership_view(&mut self, ui: &mut egui::Ui) {
+        let mismatches = self.mismatches();
+
+        ui.heading(format!("Account Mismatches ({})", mismatches.len()));
+        ui.label("These games are saved to an account that doesn't appear to own them, launching them will likely fail.");
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_salt("mismatches")
+            .show(ui, |ui| {
+                egui::Grid::new("mismatch_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Game");
+                        ui.strong("Owned by");
+                        ui.strong("Account");
+                        ui.end_row();
+
+                        for app in mismatches {
+                            if ui.link(&app.name).clicked() {
+                                self.selected_app = Some(app.clone());
+                            }
+                            let owners: Vec<&str> = self.visible_owners(&app.id).iter().map(|x| self.account_name(x)).collect();
+                            if owners.is_empty() {
+                                ui.weak("No account");
+                            } else {
+                                ui.label(owners.join(", "));
+                            }
+                            ui.allocate_ui(egui::Vec2::new(200.0, ui.spacing().interact_size.y), |ui| {
+                                self.account_picker(ui, &app, app.id);
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// Show all games owned by several accounts to pick the account of each in one place
+    pub(super) fn conflicts_view(&mut self, ui: &mut egui::Ui) {
+        let conflicts = self.conflicts();
+
+        ui.heading(format!("Multiple Owners ({})", conflicts.len()));
+        ui.label("These games are owned by more than one account. Pick the account each of them should launch with.");
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_salt("conflicts")
+            .show(ui, |ui| {
+                egui::Grid::new("conflict_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Game");
+                        ui.strong("Choice");
+                        ui.strong("Accounts");
+                        ui.end_row();
+
+                        for app in conflicts {
+                            if ui.link(&app.name).clicked() {
+                                self.selected_app = Some(app.clone());
+                            }
+                            if self.manual_logins.contains(&app) {
+                                ui.label("Manual");
+                            } else {
+                                ui.weak("Automatic");
+                            }
+
+                            let owners: Vec<SteamAccount> = self.visible_owners(&app.id).into_iter().cloned().collect();
+                            let mut game_account = self.saved_logins.get(&app).cloned();
+                            ui.horizontal(|ui| {
+                                for steam_account in &owners {
+                                    ui.radio_value(
+                                        &mut game_account,
+                                        Some(steam_account.name().to_string()),
+                                        format!("{} ({})", self.account_text(steam_account), format_stats(steam_account, &app.id)),
+                                    );
+                                }
+                            });
+                            if let Some(game_account) = game_account {
+                                self.set_game_account(&app, game_account);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
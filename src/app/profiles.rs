@@ -0,0 +1,225 @@
+use egui::{Color32, Vec2};
+
+use super::accounts::account_option;
+use super::steam::prelude::*;
+use super::App;
+
+/// Color used to flag invalid input
+pub(super) const INVALID_COLOR: Color32 = Color32::from_rgb(180, 50, 50);
+
+/// Color used to flag profiles that restart Steam
+const RESTARTS_COLOR: Color32 = Color32::from_rgb(200, 150, 40);
+
+/// Check the name of a profile, names have to be non empty and unique among the profiles of a game
+fn name_error<'a>(name: &str, mut others: impl Iterator<Item = &'a str>) -> Option<&'static str> {
+    if name.trim().is_empty() {
+        Some("The name can't be empty")
+    } else if others.any(|x| x.trim().eq_ignore_ascii_case(name.trim())) {
+        Some("Another profile has this name")
+    } else {
+        None
+    }
+}
+
+impl App {
+    /// Get a launch profile of a game by name
+    pub(super) fn launch_profile(&self, app: &AppID, name: &str) -> Option<&LaunchProfile> {
+        self.launch_profiles.get(app)?.iter().find(|x| x.name == name)
+    }
+
+    /// Show the Launch button, with a menu to launch with one of the game's profiles
+    pub(super) fn launch_button(&mut self, ui: &mut egui::Ui, app: &AppID, size: Vec2) {
+        // Environment variables only reach the game through a restarted Steam
+        let profiles: Vec<(String, bool)> = self.launch_profiles
+            .get(app)
+            .map(|x| x.iter().map(|x| (x.name.clone(), !x.env().is_empty())).collect())
+            .unwrap_or_default();
+
+        if profiles.is_empty() {
+            if ui.add_sized(size, egui::Button::new("Launch")).clicked() {
+                self.launch_app(app, None);
+            }
+            return;
+        }
+
+        let menu_width = 24.0;
+        let launch_size = Vec2::new(size.x - menu_width - ui.spacing().item_spacing.x, size.y);
+        ui.horizontal(|ui| {
+            if ui.add_sized(launch_size, egui::Button::new("Launch")).clicked() {
+                self.launch_app(app, None);
+            }
+            ui.allocate_ui(Vec2::new(menu_width, size.y), |ui| {
+                ui.menu_button("⏷", |ui| {
+                    for (name, restarts) in profiles {
+                        let button = if restarts {
+                            ui.button(format!("{} (restarts Steam)", name))
+                                .on_hover_text("This profile sets environment variables, Steam is restarted to pass them on")
+                        } else {
+                            ui.button(&name)
+                        };
+                        if button.clicked() {
+                            self.launch_app(app, Some(name));
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+        });
+    }
+
+    /// Show the editor for the launch profiles of a game
+    pub(super) fn launch_profiles_section(&mut self, ui: &mut egui::Ui, app: &AppID) {
+        let count = self.launch_profiles.get(app).map(|x| x.len()).unwrap_or_default();
+        let accounts: Vec<SteamAccount> = self.visible_accounts().into_iter().cloned().collect();
+
+        egui::CollapsingHeader::new(format!("Launch Profiles ({})", count))
+            .id_salt(("launch_profiles", app.id))
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut profiles = self.launch_profiles.get(app).cloned().unwrap_or_default();
+                let names: Vec<String> = profiles.iter().map(|x| x.name.clone()).collect();
+                let mut remove = None;
+
+                for (i, profile) in profiles.iter_mut().enumerate() {
+                    egui::Frame::group(ui.style()).show(ui, |ui| {
+                        egui::Grid::new(("launch_profile", app.id, i))
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                // Invalid names are kept apart until they are fixed, the saved name stays usable meanwhile
+                                ui.label("Name");
+                                let key = (app.id, i);
+                                let mut name = self.profile_name_edit.get(&key).cloned().unwrap_or(profile.name.clone());
+                                ui.horizontal(|ui| {
+                                    let changed = ui.text_edit_singleline(&mut name).changed();
+                                    let others = names.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, x)| x.as_str());
+                                    let error = name_error(&name, others);
+                                    if changed {
+                                        match error {
+                                            Some(_) => {
+                                                self.profile_name_edit.insert(key, name);
+                                            },
+                                            None => {
+                                                profile.name = name;
+                                                self.profile_name_edit.remove(&key);
+                                            },
+                                        }
+                                    }
+                                    if let Some(error) = error {
+                                        ui.colored_label(INVALID_COLOR, error);
+                                    }
+                                });
+                                ui.end_row();
+
+                                ui.label("Account");
+                                egui::ComboBox::from_id_salt(("launch_profile_account", app.id, i))
+                                    .selected_text(profile.account.as_deref().unwrap_or("Game account"))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut profile.account, None, "Game account");
+                                        for account in &accounts {
+                                            account_option(ui, &mut profile.account, Some(account.name().to_string()), account, self.account_text(account));
+                                        }
+                                    });
+                                ui.end_row();
+
+                                ui.label("Arguments")
+                                    .on_hover_text("Passed to the game, separated by spaces");
+                                ui.text_edit_singleline(&mut profile.args);
+                                ui.end_row();
+
+                                ui.label("Environment")
+                                    .on_hover_text("One KEY=VALUE per line, setting these restarts Steam");
+                                ui.add(egui::TextEdit::multiline(&mut profile.env).desired_rows(2));
+                                ui.end_row();
+                            });
+
+                        if !profile.env().is_empty() {
+                            ui.colored_label(RESTARTS_COLOR, "⚠ Launching this profile always restarts Steam, even on the right account");
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.button("Launch").clicked() {
+                                self.launch_app(app, Some(profile.name.clone()));
+                            }
+                            if ui.button("Remove").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                    });
+                }
+
+                if let Some(i) = remove {
+                    profiles.remove(i);
+                    // Unsaved names belong to positions that just moved
+                    self.profile_name_edit.retain(|(id, _), _| *id != app.id);
+                }
+                if ui.button("Add Profile").clicked() {
+                    let name = (profiles.len() + 1..)
+                        .map(|x| format!("Profile {}", x))
+                        .find(|x| name_error(x, profiles.iter().map(|x| x.name.as_str())).is_none())
+                        .unwrap_or_default();
+                    profiles.push(LaunchProfile {
+                        name,
+                        ..Default::default()
+                    });
+                }
+
+                let current = self.launch_profiles.get(app).cloned().unwrap_or_default();
+                if profiles != current {
+                    if profiles.is_empty() {
+                        self.launch_profiles.remove(app);
+                    } else {
+                        self.launch_profiles.insert(app.clone(), profiles);
+                    }
+                    self.needs_save = true;
+                }
+            });
+    }
+
+    /// Show and edit the launch options each account set for a game in Steam
+    ///
+    /// Only accounts that have the game in their `localconfig.vdf` are listed, saving needs Steam to be closed
+    pub(super) fn steam_launch_options_section(&mut self, ui: &mut egui::Ui, app: &AppID) {
+        let accounts: Vec<SteamAccount> = self.visible_accounts()
+            .into_iter()
+            .filter(|x| x.stats.contains_key(&app.id))
+            .cloned()
+            .collect();
+        let count = accounts.iter().filter(|x| x.launch_options(&app.id).is_some()).count();
+
+        egui::CollapsingHeader::new(format!("Steam Launch Options ({})", count))
+            .id_salt(("steam_launch_options", app.id))
+            .default_open(false)
+            .show(ui, |ui| {
+                if accounts.is_empty() {
+                    ui.weak("No account has played this game yet");
+                    return;
+                }
+                ui.weak("Saving needs Steam to be closed");
+
+                for account in &accounts {
+                    let key = (account.name().to_string(), app.id);
+                    let current = account.launch_options(&app.id).unwrap_or_default().to_string();
+                    let mut options = self.launch_options_edit.get(&key).cloned().unwrap_or(current.clone());
+
+                    ui.label(self.account_rich_text(account));
+                    ui.horizontal(|ui| {
+                        let width = ui.available_width() - 50.0;
+                        if ui.add(egui::TextEdit::singleline(&mut options).desired_width(width).hint_text("%command%")).changed() {
+                            self.launch_options_edit.insert(key.clone(), options.clone());
+                        }
+                        if ui.add_enabled(options != current, egui::Button::new("Save")).clicked() {
+                            match self.steam_model.set_launch_options(account.name(), &app.id, &options) {
+                                Ok(_) => {
+                                    self.launch_options_edit.remove(&key);
+                                    self.toasts.success(format!("Saved launch options of {} for {}", app.name, account.name()));
+                                },
+                                Err(e) => {
+                                    log::error!("Failed to save launch options: {}", e);
+                                    self.toasts.error(format!("Failed to save launch options: {}", e));
+                                },
+                            }
+                        }
+                    });
+                }
+            });
+    }
+}
@@ -0,0 +1,106 @@
+use std::{collections::HashMap, time::{Duration, Instant}};
+
+use egui::Color32;
+
+use super::format::format_ago;
+use super::steam::{backups_root, normalize_path, prelude::*};
+use super::App;
+
+const RESTORED_COLOR: Color32 = Color32::from_rgb(90, 170, 90);
+const LATER_CHANGES_COLOR: Color32 = Color32::from_rgb(200, 150, 40);
+
+/// The registry value or file a change was made to
+fn subject(change: &Change) -> String {
+    match change {
+        Change::Registry { name, .. } => format!("registry:{}", name.to_lowercase()),
+        Change::File { path, .. } => format!("file:{}", normalize_path(path)),
+    }
+}
+
+impl App {
+    /// Show every recorded change to the Steam config with a button to restore its previous state
+    pub(super) fn restore_view(&mut self, ui: &mut egui::Ui) {
+        // The launch pipeline records changes from its own thread
+        if self.journal_reloaded.is_none_or(|x| x.elapsed() > Duration::from_secs(2)) {
+            self.journal = self.steam_model.journal();
+            self.journal_reloaded = Some(Instant::now());
+        }
+
+        ui.heading("Restore");
+        ui.label(format!(
+            "Every registry value and file wait changes is backed up first. Recorded changes older than {} days are removed on startup, archived userdata is always kept.",
+            self.backup_days,
+        ));
+        ui.horizontal(|ui| {
+            ui.label("Keep backups for");
+            if ui.add(egui::DragValue::new(&mut self.backup_days).range(1..=365).suffix(" days")).changed() {
+                self.needs_save = true;
+            }
+            if ui.button("Open Backups Folder").clicked() {
+                let path = backups_root();
+                if let Err(e) = std::fs::create_dir_all(&path).and_then(|_| open::that(&path)) {
+                    self.toasts.error(format!("Failed to open {}: {}", path.display(), e));
+                }
+            }
+        });
+        ui.separator();
+
+        if self.journal.is_empty() {
+            ui.weak("No changes recorded yet");
+            return;
+        }
+
+        let mut restore = None;
+        egui::ScrollArea::vertical()
+            .id_salt("restore")
+            .show(ui, |ui| {
+                egui::Grid::new("restore_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("When");
+                        ui.strong("Change");
+                        ui.strong("Previous State");
+                        ui.strong("");
+                        ui.end_row();
+
+                        // Restoring puts back the whole previous state, undoing every later change to the same value or file
+                        let mut later: HashMap<String, usize> = HashMap::new();
+                        for entry in &self.journal {
+                            let later = later.entry(subject(&entry.change)).or_default();
+                            ui.label(format_ago(entry.timestamp));
+                            ui.label(&entry.description);
+                            ui.label(entry.target());
+                            ui.horizontal(|ui| {
+                                if ui.button("Restore").clicked() {
+                                    restore = Some(entry.clone());
+                                }
+                                if let Some(restored) = entry.restored {
+                                    ui.colored_label(RESTORED_COLOR, format!("Restored {}", format_ago(restored)));
+                                }
+                                if *later > 0 {
+                                    ui.colored_label(LATER_CHANGES_COLOR, format!("⚠ Changed {} more times since", later))
+                                        .on_hover_text(match entry.change {
+                                            Change::Registry { .. } => format!("Restoring this also undoes the {} later changes to this registry value", later),
+                                            Change::File { .. } => format!("Restoring this puts back the whole file, undoing the {} later changes to it", later),
+                                        });
+                                }
+                            });
+                            *later += 1;
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(entry) = restore {
+            match self.steam_model.restore_change(&entry) {
+                Ok(_) => self.toasts.success(format!("Restored {}", entry.target())),
+                Err(e) => {
+                    log::error!("Failed to restore change {}: {}", entry.id, e);
+                    self.toasts.error(format!("Failed to restore: {}", e))
+                },
+            };
+            self.journal_reloaded = None;
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use core::fmt;
+use std::path::Path;
+
+use regex::Regex;
+
+use super::profiles::INVALID_COLOR;
+use super::steam::{normalize_path, prelude::*};
+use super::App;
+
+/// Rule Condition
+///
+/// What a game has to match for a rule to apply
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleCondition {
+    NameMatches(String),
+    LibraryFolder(String),
+    Developer(String),
+    SingleOwner,
+}
+
+impl fmt::Display for RuleCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleCondition::NameMatches(_) => write!(f, "Name matches"),
+            RuleCondition::LibraryFolder(_) => write!(f, "Library folder is"),
+            RuleCondition::Developer(_) => write!(f, "Developer is"),
+            RuleCondition::SingleOwner => write!(f, "Owned by one account"),
+        }
+    }
+}
+
+impl RuleCondition {
+    /// Get every condition kind with an empty value
+    pub fn kinds() -> [RuleCondition; 4] {
+        [
+            RuleCondition::NameMatches(String::new()),
+            RuleCondition::LibraryFolder(String::new()),
+            RuleCondition::Developer(String::new()),
+            RuleCondition::SingleOwner,
+        ]
+    }
+
+    pub fn same_kind(&self, other: &RuleCondition) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut String> {
+        match self {
+            RuleCondition::NameMatches(value) | RuleCondition::LibraryFolder(value) | RuleCondition::Developer(value) => Some(value),
+            RuleCondition::SingleOwner => None,
+        }
+    }
+}
+
+/// Rule
+///
+/// Assigns an account to games matching its condition
+///
+/// # Variables
+///
+/// - `condition` - The condition a game has to match
+/// - `account` - The account to assign, `None` assigns the only owning account
+/// - `enabled` - Disabled rules are skipped
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub condition: RuleCondition,
+    pub account: Option<String>,
+    pub enabled: bool,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self {
+            condition: RuleCondition::SingleOwner,
+            account: None,
+            enabled: true,
+        }
+    }
+}
+
+/// Rules prepared for matching against a whole library
+///
+/// Kept by the app and only compiled again once the rules change
+#[derive(Debug, Default)]
+pub struct RuleMatcher {
+    rules: Vec<Rule>,
+    /// The compiled name pattern of each rule, or why it failed to compile
+    patterns: Vec<Option<Result<Regex, String>>>,
+}
+
+impl RuleMatcher {
+    pub fn new(rules: &[Rule]) -> Self {
+        let patterns = rules
+            .iter()
+            .map(|rule| match &rule.condition {
+                RuleCondition::NameMatches(pattern) => Some(Regex::new(&format!("(?i){}", pattern)).map_err(|e| e.to_string())),
+                _ => None,
+            })
+            .collect();
+
+        Self { rules: rules.to_vec(), patterns }
+    }
+
+    /// Whether the matcher was prepared from `rules`
+    pub fn is_for(&self, rules: &[Rule]) -> bool {
+        self.rules == rules
+    }
+
+    /// Get why the name pattern of a rule is invalid
+    pub fn error(&self, rule: usize) -> Option<&str> {
+        match self.patterns.get(rule)? {
+            Some(Err(e)) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Find the first enabled rule matching a game
+    ///
+    /// Returns the index of the rule and the account it assigns
+    pub fn find(&self, app: &AppID, info: Option<&AppInfo>, owners: &[&SteamAccount]) -> Option<(usize, String)> {
+        for (i, (rule, pattern)) in self.rules.iter().zip(&self.patterns).enumerate() {
+            if !rule.enabled {
+                continue;
+            }
+
+            let matched = match &rule.condition {
+                RuleCondition::NameMatches(_) => matches!(pattern, Some(Ok(regex)) if regex.is_match(&app.name)),
+                RuleCondition::LibraryFolder(folder) => {
+                    !folder.trim().is_empty() && normalize_path(&app.location).starts_with(&normalize_path(Path::new(folder.trim())))
+                },
+                RuleCondition::Developer(developer) => info
+                    .and_then(|x| x.developer.as_ref())
+                    .is_some_and(|x| x.split(',').any(|x| x.trim().eq_ignore_ascii_case(developer.trim()))),
+                RuleCondition::SingleOwner => owners.len() == 1,
+            };
+            if !matched {
+                continue;
+            }
+
+            let account = match &rule.account {
+                Some(account) => Some(account.clone()),
+                None if owners.len() == 1 => Some(owners[0].name().to_string()),
+                None => None,
+            };
+            if let Some(account) = account {
+                return Some((i, account));
+            }
+        }
+
+        None
+    }
+}
+
+impl App {
+    /// Compile the rules again if they changed since the matcher was prepared
+    pub(super) fn refresh_rule_matcher(&mut self) {
+        if !self.rule_matcher.is_for(&self.rules) {
+            self.rule_matcher = RuleMatcher::new(&self.rules);
+        }
+    }
+
+    /// Preview which installed games the rules would assign
+    ///
+    /// Games with a manual choice are skipped.
+    /// Returns each game with the index of the matching rule and its account
+    pub(super) fn preview_rules(&self) -> Vec<(AppID, usize, String)> {
+        let mut preview: Vec<(AppID, usize, String)> = self.steam_model
+            .get_installed_apps()
+            .into_iter()
+            .filter(|app| !self.manual_logins.contains(app))
+            .filter_map(|app| {
+                let owners = self.owners(&app.id);
+                let (rule, account) = self.rule_matcher.find(&app, self.steam_model.app_info.get(&app.id), &owners)?;
+                Some((app, rule, account))
+            })
+            .collect();
+        preview.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+        preview
+    }
+
+    /// Show the rule editor with a preview of the assignments
+    pub(super) fn rules_editor(&mut self, ui: &mut egui::Ui) {
+        self.refresh_rule_matcher();
+        let accounts: Vec<String> = self.visible_accounts().iter().map(|x| x.name().to_string()).collect();
+        let folders: Vec<String> = self.steam_model.directories.keys().map(|x| x.to_string_lossy().to_string()).collect();
+
+        let mut swap = None;
+        let mut remove = None;
+        let count = self.rules.len();
+        for (i, rule) in self.rules.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut rule.enabled, format!("{}.", i + 1));
+
+                egui::ComboBox::from_id_salt(("rule_condition", i))
+                    .selected_text(rule.condition.to_string())
+                    .show_ui(ui, |ui| {
+                        for kind in RuleCondition::kinds() {
+                            if ui.selectable_label(rule.condition.same_kind(&kind), kind.to_string()).clicked() && !rule.condition.same_kind(&kind) {
+                                rule.condition = kind;
+                            }
+                        }
+                    });
+
+                let is_folder = matches!(rule.condition, RuleCondition::LibraryFolder(_));
+                let error = self.rule_matcher.error(i);
+                if let Some(value) = rule.condition.value_mut() {
+                    let edit = ui.add(egui::TextEdit::singleline(value).desired_width(160.0).text_color_opt(error.map(|_| INVALID_COLOR)));
+                    if let Some(error) = error {
+                        edit.on_hover_text(error);
+                        ui.colored_label(INVALID_COLOR, "⚠ Invalid pattern");
+                    }
+                    if is_folder {
+                        ui.menu_button("📁", |ui| {
+                            for folder in &folders {
+                                if ui.button(folder).clicked() {
+                                    *value = folder.clone();
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    }
+                }
+
+                ui.label("→");
+                egui::ComboBox::from_id_salt(("rule_account", i))
+                    .selected_text(rule.account.as_deref().unwrap_or("Only owner"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut rule.account, None, "Only owner");
+                        for account in &accounts {
+                            ui.selectable_value(&mut rule.account, Some(account.clone()), account);
+                        }
+                    });
+
+                if ui.add_enabled(i > 0, egui::Button::new("⬆")).clicked() {
+                    swap = Some((i, i - 1));
+                }
+                if ui.add_enabled(i + 1 < count, egui::Button::new("⬇")).clicked() {
+                    swap = Some((i, i + 1));
+                }
+                if ui.button("🗑").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some((a, b)) = swap {
+            self.rules.swap(a, b);
+        }
+        if let Some(i) = remove {
+            self.rules.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Add rule").clicked() {
+                self.rules.push(Rule::default());
+            }
+            if ui.button("Apply rules")
+                .on_hover_text("Reassign every game that was not assigned by hand")
+                .clicked()
+            {
+                let count = self.assign_logins(true);
+                self.toasts.info(format!("Assigned accounts to {} games", count));
+                self.needs_save = true;
+            }
+        });
+
+        egui::CollapsingHeader::new("Preview")
+            .show(ui, |ui| {
+                let preview = self.preview_rules();
+                if preview.is_empty() {
+                    ui.weak("No games match the rules");
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .id_salt("rules_preview")
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("rules_preview_grid")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Game");
+                                ui.strong("Rule");
+                                ui.strong("Current");
+                                ui.strong("New");
+                                ui.end_row();
+
+                                for (app, rule, account) in preview {
+                                    let current = self.saved_logins.get(&app).cloned().unwrap_or_default();
+                                    ui.label(&app.name);
+                                    ui.label(format!("{}", rule + 1));
+                                    ui.label(&current);
+                                    if current == account {
+                                        ui.label(account);
+                                    } else {
+                                        ui.strong(account);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+    }
+}
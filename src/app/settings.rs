@@ -0,0 +1,125 @@
+use super::{accounts::account_option, assign::AssignPolicy, hooks::hooks_editor, App};
+
+impl App {
+    /// Show the settings window if it is open
+    pub(super) fn settings_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.settings_open;
+
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_salt("settings")
+                    .show(ui, |ui| {
+                        ui.heading("Launch");
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut self.launch_config.close_timeout)
+                                    .range(5..=600)
+                                    .suffix("s")
+                            );
+                            ui.label("Steam close timeout");
+                        });
+                        ui.checkbox(&mut self.launch_config.force_kill, "Force close Steam after timeout");
+                        if ui.checkbox(&mut self.steam_model.dry_run, "Dry run")
+                            .on_hover_text("Only show what logins and launches would change, without touching the registry, files or Steam")
+                            .changed()
+                        {
+                            self.dry_run = self.steam_model.dry_run;
+                            self.needs_save = true;
+                        }
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut self.launch_config.login_timeout)
+                                    .range(10..=600)
+                                    .suffix("s")
+                            );
+                            ui.label("Steam login timeout");
+                        });
+
+                        ui.separator();
+                        ui.heading("Switch Back");
+                        ui.checkbox(&mut self.restore_all, "Switch back account after every game exits");
+                        let mut home_account = self.home_account.clone();
+                        egui::ComboBox::from_label("Switch back to")
+                            .selected_text(self.home_account.as_deref().unwrap_or("Previous account"))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut home_account, None, "Previous account");
+                                for steam_account in self.visible_accounts() {
+                                    account_option(
+                                        ui,
+                                        &mut home_account,
+                                        Some(steam_account.name().to_string()),
+                                        steam_account,
+                                        self.account_rich_text(steam_account),
+                                    );
+                                }
+                            });
+                        self.home_account = home_account;
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut self.launch_config.game_timeout)
+                                    .range(10..=600)
+                                    .suffix("s")
+                            );
+                            ui.label("Game start timeout");
+                        });
+
+                        ui.separator();
+                        ui.heading("Hooks");
+                        ui.label("Run around every game launch");
+                        if hooks_editor(ui, &mut self.launch_config.hooks, "global_hooks") {
+                            self.needs_save = true;
+                        }
+
+                        ui.separator();
+                        ui.heading("Account Assignment");
+                        egui::ComboBox::from_label("Games owned by several accounts")
+                            .selected_text(self.assign_policy.to_string())
+                            .show_ui(ui, |ui| {
+                                for policy in AssignPolicy::ALL {
+                                    ui.selectable_value(&mut self.assign_policy, policy, policy.to_string());
+                                }
+                            });
+
+                        if self.assign_policy == AssignPolicy::Priority {
+                            self.account_order = self.ordered_accounts().iter().map(|x| x.name().to_string()).collect();
+
+                            let mut swap = None;
+                            for (i, name) in self.account_order.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    if ui.add_enabled(i > 0, egui::Button::new("⬆")).clicked() {
+                                        swap = Some((i, i - 1));
+                                    }
+                                    if ui.add_enabled(i + 1 < self.account_order.len(), egui::Button::new("⬇")).clicked() {
+                                        swap = Some((i, i + 1));
+                                    }
+                                    ui.label(format!("{}. {}", i + 1, name));
+                                });
+                            }
+                            if let Some((a, b)) = swap {
+                                self.account_order.swap(a, b);
+                            }
+                        }
+
+                        if ui.button("Re-evaluate automatic assignments")
+                            .on_hover_text("Reassign every game that was not assigned by hand")
+                            .clicked()
+                        {
+                            let count = self.assign_logins(true);
+                            self.toasts.info(format!("Assigned accounts to {} games", count));
+                            self.needs_save = true;
+                        }
+
+                        ui.separator();
+                        ui.heading("Assignment Rules");
+                        ui.label("Rules are checked in order for games without a manual choice, before the policy above.");
+                        self.rules_editor(ui);
+                    });
+            });
+
+        self.settings_open = open;
+    }
+}
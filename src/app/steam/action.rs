@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// Client Action
+///
+/// Per game actions of the Steam client, opened through `steam://` urls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientAction {
+    Validate,
+    Uninstall,
+    StorePage,
+    Properties,
+    Backup,
+    CommunityHub,
+}
+
+impl fmt::Display for ClientAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientAction::Validate => write!(f, "Verify Integrity"),
+            ClientAction::Uninstall => write!(f, "Uninstall"),
+            ClientAction::StorePage => write!(f, "Store Page"),
+            ClientAction::Properties => write!(f, "Properties"),
+            ClientAction::Backup => write!(f, "Backup"),
+            ClientAction::CommunityHub => write!(f, "Community Hub"),
+        }
+    }
+}
+
+impl ClientAction {
+    pub const ALL: [ClientAction; 6] = [
+        ClientAction::Validate,
+        ClientAction::Uninstall,
+        ClientAction::StorePage,
+        ClientAction::Properties,
+        ClientAction::Backup,
+        ClientAction::CommunityHub,
+    ];
+
+    pub fn url(&self, appid: i32) -> String {
+        match self {
+            ClientAction::Validate => format!("steam://validate/{}", appid),
+            ClientAction::Uninstall => format!("steam://uninstall/{}", appid),
+            ClientAction::StorePage => format!("steam://store/{}", appid),
+            ClientAction::Properties => format!("steam://gameproperties/{}", appid),
+            ClientAction::Backup => format!("steam://backup/{}", appid),
+            ClientAction::CommunityHub => format!("steam://url/GameHub/{}", appid),
+        }
+    }
+}
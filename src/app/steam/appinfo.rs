@@ -0,0 +1,299 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use super::data::AppInfo;
+use super::manifest::ManifestParseError;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const MAGIC_V27: u32 = 0x07564427;
+const MAGIC_V28: u32 = 0x07564428;
+const MAGIC_V29: u32 = 0x07564429;
+
+/// Binary key value types
+const TYPE_MAP: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT32: u8 = 0x02;
+const TYPE_FLOAT32: u8 = 0x03;
+const TYPE_POINTER: u8 = 0x04;
+const TYPE_WIDESTRING: u8 = 0x05;
+const TYPE_COLOR: u8 = 0x06;
+const TYPE_UINT64: u8 = 0x07;
+const TYPE_END: u8 = 0x08;
+const TYPE_INT64: u8 = 0x0A;
+const TYPE_END_ALT: u8 = 0x0B;
+
+/// Maps nested deeper than this are treated as corrupt
+const MAX_DEPTH: usize = 64;
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    strings: Option<Vec<String>>,
+}
+
+impl<'a> Reader<'a> {
+    /// Get the position `len` bytes ahead, failing if it is past the end of the data
+    fn ahead(&self, len: usize) -> Result<usize> {
+        match self.pos.checked_add(len) {
+            Some(end) if end <= self.data.len() => Ok(end),
+            _ => Err(Box::new(ManifestParseError)),
+        }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.ahead(len)?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        self.pos = self.ahead(len)?;
+        Ok(())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into()?))
+    }
+
+    fn cstring(&mut self) -> Result<String> {
+        let rest = self.data.get(self.pos..).ok_or(ManifestParseError)?;
+        let len = rest.iter().position(|x| *x == 0).ok_or(ManifestParseError)?;
+        let value = String::from_utf8_lossy(self.bytes(len)?).to_string();
+        self.skip(1)?; // Skip the null terminator
+        Ok(value)
+    }
+
+    fn key(&mut self) -> Result<String> {
+        if self.strings.is_none() {
+            return self.cstring();
+        }
+        let index = self.u32()? as usize;
+        match self.strings.as_ref().and_then(|x| x.get(index)) {
+            Some(key) => Ok(key.clone()),
+            None => Err(Box::new(ManifestParseError)),
+        }
+    }
+
+    /// Read a binary key value map until its end marker
+    fn map(&mut self, depth: usize) -> Result<serde_json::Value> {
+        if depth > MAX_DEPTH {
+            return Err(Box::new(ManifestParseError));
+        }
+        let mut obj = serde_json::Map::new();
+
+        loop {
+            let kind = self.u8()?;
+            if kind == TYPE_END || kind == TYPE_END_ALT {
+                break;
+            }
+
+            let key = self.key()?;
+            let value = match kind {
+                TYPE_MAP => self.map(depth + 1)?,
+                TYPE_STRING => serde_json::Value::String(self.cstring()?),
+                TYPE_WIDESTRING => {
+                    let mut chars = Vec::new();
+                    loop {
+                        let ch = u16::from_le_bytes(self.bytes(2)?.try_into()?);
+                        if ch == 0 {
+                            break;
+                        }
+                        chars.push(ch);
+                    }
+                    serde_json::Value::String(String::from_utf16_lossy(&chars))
+                },
+                TYPE_INT32 | TYPE_POINTER | TYPE_COLOR => serde_json::Value::String((self.u32()? as i32).to_string()),
+                TYPE_FLOAT32 => serde_json::Value::String(f32::from_le_bytes(self.bytes(4)?.try_into()?).to_string()),
+                TYPE_UINT64 => serde_json::Value::String(self.u64()?.to_string()),
+                TYPE_INT64 => serde_json::Value::String((self.u64()? as i64).to_string()),
+                _ => return Err(Box::new(ManifestParseError)),
+            };
+            obj.insert(key, value);
+        }
+
+        Ok(serde_json::Value::Object(obj))
+    }
+}
+
+/// Parse `appcache/appinfo.vdf`
+///
+/// Only apps accepted by `filter` are decoded, the rest are skipped.
+/// Supports the v27, v28 and v29 (string table) formats
+pub fn parse_appinfo(path: PathBuf, filter: impl Fn(i32) -> bool) -> Result<HashMap<i32, AppInfo>> {
+    parse_data(&std::fs::read(path)?, filter)
+}
+
+/// Parse the contents of `appinfo.vdf`, every offset and size is checked against the data
+fn parse_data(data: &[u8], filter: impl Fn(i32) -> bool) -> Result<HashMap<i32, AppInfo>> {
+    let mut reader = Reader { data, pos: 0, strings: None };
+
+    let magic = reader.u32()?;
+    let _universe = reader.u32()?;
+    if magic == MAGIC_V29 {
+        // Keys are stored once in a table at the end of the file
+        let offset = usize::try_from(reader.u64()?)?;
+        let mut table = Reader { data, pos: offset, strings: None };
+        let count = table.u32()?;
+        // The count isn't trusted for allocating, a corrupt one runs out of data instead
+        let mut strings = Vec::new();
+        for _ in 0..count {
+            strings.push(table.cstring()?);
+        }
+        reader.strings = Some(strings);
+    } else if magic != MAGIC_V27 && magic != MAGIC_V28 {
+        log::warn!("Unsupported appinfo.vdf version: {:#x}", magic);
+        return Err(Box::new(ManifestParseError));
+    }
+
+    let mut apps = HashMap::new();
+    loop {
+        let appid = reader.u32()?;
+        if appid == 0 {
+            break;
+        }
+        let size = reader.u32()? as usize;
+        let end = reader.ahead(size)?;
+
+        if !filter(appid as i32) {
+            reader.pos = end;
+            continue;
+        }
+
+        // info state, last updated, pics token, sha1, change number and (v28+) binary sha1
+        reader.skip(4 + 4 + 8 + 20 + 4)?;
+        if magic != MAGIC_V27 {
+            reader.skip(20)?;
+        }
+
+        let value = reader.map(0)?;
+        let value = value.get("appinfo").unwrap_or(&value);
+        apps.insert(appid as i32, AppInfo::from(value));
+
+        reader.pos = end;
+    }
+
+    Ok(apps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a binary key value entry, keys are written as is
+    fn entry(kind: u8, key: &[u8], value: &[u8]) -> Vec<u8> {
+        [&[kind][..], key, value].concat()
+    }
+
+    /// Build `appinfo.vdf` with one app named `name`, keys are either strings or string table indices
+    fn appinfo(magic: u32, appid: u32, key: impl Fn(&str) -> Vec<u8>, table: &[&str]) -> Vec<u8> {
+        let string = |x: &str| [x.as_bytes(), &[0]].concat();
+        let common = [
+            entry(TYPE_STRING, &key("name"), &string("Team Fortress 2")),
+            entry(TYPE_STRING, &key("type"), &string("Game")),
+            vec![TYPE_END],
+        ].concat();
+        let map = [
+            entry(TYPE_MAP, &key("appinfo"), &[entry(TYPE_MAP, &key("common"), &common), vec![TYPE_END]].concat()),
+            vec![TYPE_END],
+        ].concat();
+
+        let mut app = vec![0; 60];
+        app.extend(map);
+
+        let mut data = Vec::new();
+        data.extend(magic.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        let offset_at = data.len();
+        if magic == MAGIC_V29 {
+            data.extend(0u64.to_le_bytes());
+        }
+        data.extend(appid.to_le_bytes());
+        data.extend((app.len() as u32).to_le_bytes());
+        data.extend(app);
+        data.extend(0u32.to_le_bytes());
+
+        if magic == MAGIC_V29 {
+            let offset = data.len() as u64;
+            data[offset_at..offset_at + 8].copy_from_slice(&offset.to_le_bytes());
+            data.extend((table.len() as u32).to_le_bytes());
+            for key in table {
+                data.extend(string(key));
+            }
+        }
+        data
+    }
+
+    const TABLE: [&str; 4] = ["appinfo", "common", "name", "type"];
+
+    fn v28() -> Vec<u8> {
+        appinfo(MAGIC_V28, 440, |x| [x.as_bytes(), &[0]].concat(), &[])
+    }
+
+    fn v29() -> Vec<u8> {
+        appinfo(MAGIC_V29, 440, |x| (TABLE.iter().position(|k| *k == x).unwrap() as u32).to_le_bytes().to_vec(), &TABLE)
+    }
+
+    fn tf2() -> AppInfo {
+        AppInfo {
+            name: "Team Fortress 2".to_string(),
+            kind: "game".to_string(),
+            developer: None,
+            publisher: None,
+        }
+    }
+
+    #[test]
+    fn parse_v28() {
+        let apps = parse_data(&v28(), |_| true).unwrap();
+        assert_eq!(apps.get(&440), Some(&tf2()));
+    }
+
+    #[test]
+    fn parse_v29() {
+        let apps = parse_data(&v29(), |_| true).unwrap();
+        assert_eq!(apps.get(&440), Some(&tf2()));
+    }
+
+    #[test]
+    fn filtered_apps_are_skipped() {
+        assert!(parse_data(&v28(), |x| x != 440).unwrap().is_empty());
+    }
+
+    #[test]
+    fn truncated_input() {
+        for data in [v28(), v29()] {
+            for len in 0..data.len() {
+                assert!(parse_data(&data[..len], |_| true).is_err(), "{} bytes", len);
+            }
+        }
+    }
+
+    #[test]
+    fn bad_string_table() {
+        let mut data = v29();
+        data[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(parse_data(&data, |_| true).is_err());
+
+        // A count far beyond the data
+        let mut data = v29();
+        let offset = u64::from_le_bytes(data[8..16].try_into().unwrap()) as usize;
+        data[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_data(&data, |_| true).is_err());
+    }
+
+    #[test]
+    fn bad_app_size() {
+        let mut data = v28();
+        data[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_data(&data, |x| x != 440).is_err());
+    }
+}
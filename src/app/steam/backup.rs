@@ -0,0 +1,50 @@
+use std::{fs, io, path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Get the folder all backups are stored in, next to the saved app state
+pub fn backups_root() -> PathBuf {
+    eframe::storage_dir("wait")
+        .unwrap_or_else(std::env::temp_dir)
+        .join("backups")
+}
+
+/// Create a new backup folder named after the current time and `label`
+pub fn create_backup(label: &str) -> Result<PathBuf> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let label: String = label
+        .chars()
+        .map(|x| if x.is_ascii_alphanumeric() || x == '-' || x == '_' { x } else { '_' })
+        .collect();
+
+    let path = backups_root().join(format!("{}-{}", timestamp, label));
+    fs::create_dir_all(&path)?;
+    Ok(path)
+}
+
+/// Copy a folder with all its contents
+pub fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move a folder, copying it if it has to cross drives
+pub fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_dir(from, to)?;
+    fs::remove_dir_all(from)
+}
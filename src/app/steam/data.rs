@@ -1,28 +1,37 @@
 use core::fmt;
-use std::{collections::HashSet, path::PathBuf};
+use std::{collections::{HashMap, HashSet}, path::PathBuf};
 
-#[derive(serde::Deserialize, serde::Serialize)]
-#[derive(Debug, Default, PartialEq, Eq, Clone)]
-pub struct SteamID {
-    pub id3: i64,
-    pub id64: i64,
-}
-
-impl From<i64> for SteamID {
-    fn from(id64: i64) -> Self {
-        Self {
-            id3: (id64 & 0xFFFFFFFF),
-            id64,
-        }
-    }
-}
+use super::steamid::SteamID;
 
+/// Steam Account
+/// 
+/// # Variables
+/// 
+/// - `name` - The login name
+/// - `id` - The SteamID
+/// - `games` - Apps listed in the account's `localconfig.vdf`
+/// - `stats` - Per app play time and last played date
+/// - `persona_name` - The display name of the account
+/// - `timestamp` - Unix timestamp of the last login
+/// - `most_recent` - Whether this was the last account to log in
+/// - `remember_password` - Whether Steam remembers the password
+/// - `allow_auto_login` - Whether Steam allows logging in without a password
+/// - `wants_offline_mode` - Whether Steam starts in offline mode
+/// - `avatar` - The cached avatar in `config/avatarcache`
 #[derive(serde::Deserialize, serde::Serialize)]
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct SteamAccount {
     pub name: String,
     pub id: Option<SteamID>,
     pub games: HashSet<i32>,
+    pub stats: HashMap<i32, AppStats>,
+    pub persona_name: String,
+    pub timestamp: Option<u64>,
+    pub most_recent: bool,
+    pub remember_password: bool,
+    pub allow_auto_login: bool,
+    pub wants_offline_mode: bool,
+    pub avatar: Option<PathBuf>,
 }
 
 impl fmt::Display for SteamAccount {
@@ -35,10 +44,66 @@ impl SteamAccount {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Get the persona name, falling back to the login name
+    pub fn persona_name(&self) -> &str {
+        if self.persona_name.is_empty() { &self.name } else { &self.persona_name }
+    }
+
+    /// Whether Steam can log into this account without asking for the password
+    pub fn can_auto_login(&self) -> bool {
+        self.remember_password && self.allow_auto_login
+    }
+
+    pub fn stats(&self, appid: &i32) -> Option<&AppStats> {
+        self.stats.get(appid)
+    }
+
+    /// Get the minutes this account has played a game
+    pub fn playtime(&self, appid: &i32) -> u64 {
+        self.stats(appid).and_then(|x| x.playtime).unwrap_or_default()
+    }
+
+    /// Get the unix timestamp this account last played a game
+    pub fn last_played(&self, appid: &i32) -> Option<u64> {
+        self.stats(appid).and_then(|x| x.last_played)
+    }
+
+    /// Get the launch options this account set for a game in Steam
+    pub fn launch_options(&self, appid: &i32) -> Option<&str> {
+        self.stats(appid).and_then(|x| x.launch_options.as_deref())
+    }
 }
 
+/// Per account statistics of an app, read from `localconfig.vdf`
+/// 
+/// # Variables
+/// 
+/// - `last_played` - Unix timestamp of the last time the app was played
+/// - `playtime` - Total play time in minutes
+/// - `launch_options` - The launch options set in the game's Steam properties
 #[derive(serde::Deserialize, serde::Serialize)]
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct AppStats {
+    pub last_played: Option<u64>,
+    pub playtime: Option<u64>,
+    pub launch_options: Option<String>,
+}
+
+impl From<&serde_json::Value> for AppStats {
+    fn from(value: &serde_json::Value) -> Self {
+        let read = |key: &str| value.get(key).and_then(|x| x.as_str()).and_then(|x| x.parse::<u64>().ok());
+
+        Self {
+            last_played: read("LastPlayed").filter(|x| *x > 0),
+            playtime: read("Playtime"),
+            launch_options: value.get("LaunchOptions").and_then(|x| x.as_str()).filter(|x| !x.is_empty()).map(|x| x.to_string()),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Default, Clone)]
 pub struct AppID {
     pub id: i32,
     pub name: String,
@@ -52,6 +117,15 @@ impl std::hash::Hash for AppID {
     }
 }
 
+// Compared by id only, like the hash, so saved games still match after `last_played` changes
+impl PartialEq for AppID {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for AppID {}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct Thumbnail {
     pub portrait: Option<PathBuf>,
@@ -65,4 +139,296 @@ impl From<(Option<PathBuf>, Option<PathBuf>)> for Thumbnail {
             landscape,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Launch Profile
+/// 
+/// A named way to launch a game
+/// 
+/// # Variables
+/// 
+/// - `name` - The name shown in menus and used on the command line
+/// - `account` - The account to launch with, `None` uses the game's saved account
+/// - `args` - Arguments passed to the game, split on whitespace
+/// - `env` - Environment variables as `KEY=VALUE` lines
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct LaunchProfile {
+    pub name: String,
+    pub account: Option<String>,
+    pub args: String,
+    pub env: String,
+}
+
+impl LaunchProfile {
+    pub fn args(&self) -> Vec<String> {
+        self.args.split_whitespace().map(|x| x.to_string()).collect()
+    }
+
+    /// Get the environment variables, lines without a `=` are skipped
+    pub fn env(&self) -> Vec<(String, String)> {
+        self.env
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .filter(|(key, _)| !key.is_empty())
+            .collect()
+    }
+}
+
+/// App metadata read from `appcache/appinfo.vdf`
+/// 
+/// # Variables
+/// 
+/// - `name` - The store name of the app
+/// - `kind` - The app type in lowercase, e.g. `game`, `dlc` or `tool`
+/// - `developer` - The developers, comma separated
+/// - `publisher` - The publishers, comma separated
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct AppInfo {
+    pub name: String,
+    pub kind: String,
+    pub developer: Option<String>,
+    pub publisher: Option<String>,
+}
+
+impl From<&serde_json::Value> for AppInfo {
+    fn from(value: &serde_json::Value) -> Self {
+        let common = value.get("common");
+        let read = |key: &str| common.and_then(|x| x.get(key)).and_then(|x| x.as_str()).unwrap_or_default().to_string();
+
+        // Prefer the extended section, older apps only list their companies in the associations
+        let company = |key: &str| {
+            let extended = value.get("extended").and_then(|x| x.get(key)).and_then(|x| x.as_str());
+            if let Some(company) = extended.filter(|x| !x.is_empty()) {
+                return Some(company.to_string());
+            }
+
+            let associations: Vec<&str> = common
+                .and_then(|x| x.get("associations"))
+                .and_then(|x| x.as_object())
+                .map(|x| x.values()
+                    .filter(|x| x.get("type").and_then(|x| x.as_str()) == Some(key))
+                    .filter_map(|x| x.get("name").and_then(|x| x.as_str()))
+                    .collect())
+                .unwrap_or_default();
+            if associations.is_empty() { None } else { Some(associations.join(", ")) }
+        };
+
+        Self {
+            name: read("name"),
+            kind: read("type").to_lowercase(),
+            developer: company("developer"),
+            publisher: company("publisher"),
+        }
+    }
+}
+
+/// What to do with the `userdata` folder of a forgotten account
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UserdataAction {
+    #[default]
+    Keep,
+    Archive,
+    Delete,
+}
+
+impl fmt::Display for UserdataAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserdataAction::Keep => write!(f, "Keep"),
+            UserdataAction::Archive => write!(f, "Move to backups"),
+            UserdataAction::Delete => write!(f, "Delete (a full copy is backed up)"),
+        }
+    }
+}
+
+/// `StateFlags` bits of an app manifest
+mod state_flags {
+    pub const UNINSTALLED: u32 = 1 << 0;
+    pub const UPDATE_REQUIRED: u32 = 1 << 1;
+    pub const FULLY_INSTALLED: u32 = 1 << 2;
+    pub const FILES_MISSING: u32 = 1 << 5;
+    pub const FILES_CORRUPT: u32 = 1 << 7;
+    pub const UPDATE_RUNNING: u32 = 1 << 8;
+    pub const UPDATE_PAUSED: u32 = 1 << 9;
+    pub const UPDATE_STARTED: u32 = 1 << 10;
+    pub const UNINSTALLING: u32 = 1 << 11;
+    pub const VALIDATING: u32 = 1 << 17;
+    pub const ADDING_FILES: u32 = 1 << 18;
+    pub const PREALLOCATING: u32 = 1 << 19;
+    pub const DOWNLOADING: u32 = 1 << 20;
+    pub const STAGING: u32 = 1 << 21;
+    pub const COMMITTING: u32 = 1 << 22;
+
+    pub const UPDATING: u32 = UPDATE_RUNNING | UPDATE_STARTED | ADDING_FILES | PREALLOCATING | DOWNLOADING | STAGING | COMMITTING;
+}
+
+/// Install Status
+///
+/// The most important state decoded from `StateFlags`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InstallStatus {
+    Installed,
+    UpdateRequired,
+    Updating,
+    Paused,
+    Validating,
+    FilesMissing,
+    Corrupt,
+    Uninstalling,
+    Uninstalled,
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for InstallStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstallStatus::Installed => write!(f, "Installed"),
+            InstallStatus::UpdateRequired => write!(f, "Update required"),
+            InstallStatus::Updating => write!(f, "Updating"),
+            InstallStatus::Paused => write!(f, "Update paused"),
+            InstallStatus::Validating => write!(f, "Validating"),
+            InstallStatus::FilesMissing => write!(f, "Files missing"),
+            InstallStatus::Corrupt => write!(f, "Corrupt"),
+            InstallStatus::Uninstalling => write!(f, "Uninstalling"),
+            InstallStatus::Uninstalled => write!(f, "Uninstalled"),
+            InstallStatus::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// Install state of an app, read from its `appmanifest_<id>.acf`
+/// 
+/// # Variables
+/// 
+/// - `flags` - The raw `StateFlags`
+/// - `bytes_to_download` - Size of the pending update
+/// - `bytes_downloaded` - Downloaded part of the pending update
+/// - `build_id` - The installed build
+/// - `target_build_id` - The build being updated to, `0` if none
+/// - `update_result` - Error code of the last update, `0` if it succeeded
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InstallState {
+    pub flags: u32,
+    pub bytes_to_download: u64,
+    pub bytes_downloaded: u64,
+    pub build_id: u64,
+    pub target_build_id: u64,
+    pub update_result: u32,
+}
+
+impl From<&serde_json::Value> for InstallState {
+    fn from(value: &serde_json::Value) -> Self {
+        let read = |key: &str| value.get(key).and_then(|x| x.as_str()).and_then(|x| x.parse::<u64>().ok()).unwrap_or_default();
+
+        Self {
+            flags: read("StateFlags") as u32,
+            bytes_to_download: read("BytesToDownload"),
+            bytes_downloaded: read("BytesDownloaded"),
+            build_id: read("buildid"),
+            target_build_id: read("TargetBuildID"),
+            update_result: read("UpdateResult") as u32,
+        }
+    }
+}
+
+impl InstallState {
+    fn has(&self, flags: u32) -> bool {
+        self.flags & flags != 0
+    }
+
+    pub fn status(&self) -> InstallStatus {
+        use state_flags::*;
+
+        if self.has(UNINSTALLING) {
+            InstallStatus::Uninstalling
+        } else if self.has(FILES_CORRUPT) {
+            InstallStatus::Corrupt
+        } else if self.has(FILES_MISSING) {
+            InstallStatus::FilesMissing
+        } else if self.has(VALIDATING) {
+            InstallStatus::Validating
+        } else if self.has(UPDATE_PAUSED) {
+            InstallStatus::Paused
+        } else if self.has(UPDATING) {
+            InstallStatus::Updating
+        } else if self.has(UPDATE_REQUIRED) || (self.target_build_id != 0 && self.target_build_id != self.build_id) {
+            InstallStatus::UpdateRequired
+        } else if self.has(FULLY_INSTALLED) {
+            InstallStatus::Installed
+        } else if self.has(UNINSTALLED) {
+            InstallStatus::Uninstalled
+        } else {
+            InstallStatus::Unknown
+        }
+    }
+
+    /// Whether the app has a pending or unfinished update
+    pub fn needs_update(&self) -> bool {
+        matches!(self.status(), InstallStatus::UpdateRequired | InstallStatus::Updating | InstallStatus::Paused)
+    }
+
+    /// Get the download progress of the pending update between `0.0` and `1.0`
+    pub fn progress(&self) -> Option<f32> {
+        if self.bytes_to_download == 0 {
+            return None;
+        }
+        Some((self.bytes_downloaded as f64 / self.bytes_to_download as f64).clamp(0.0, 1.0) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(flags: &str) -> InstallState {
+        InstallState::from(&serde_json::json!({
+            "StateFlags": flags,
+            "buildid": "100",
+            "TargetBuildID": "0",
+        }))
+    }
+
+    #[test]
+    fn fully_installed() {
+        let state = state("4");
+        assert_eq!(state.status(), InstallStatus::Installed);
+        assert!(!state.needs_update());
+    }
+
+    #[test]
+    fn update_required() {
+        let state = state("6");
+        assert_eq!(state.status(), InstallStatus::UpdateRequired);
+        assert!(state.needs_update());
+    }
+
+    #[test]
+    fn update_started() {
+        let state = state("1026");
+        assert_eq!(state.status(), InstallStatus::Updating);
+        assert!(state.needs_update());
+    }
+
+    #[test]
+    fn update_paused() {
+        let state = state("1542");
+        assert_eq!(state.status(), InstallStatus::Paused);
+        assert!(state.needs_update());
+    }
+
+    #[test]
+    fn newer_target_build() {
+        let state = InstallState::from(&serde_json::json!({
+            "StateFlags": "4",
+            "buildid": "100",
+            "TargetBuildID": "101",
+        }));
+        assert_eq!(state.status(), InstallStatus::UpdateRequired);
+        assert!(state.needs_update());
+    }
+}
@@ -3,6 +3,8 @@
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LoginError {
     AlreadyLoggedIn,
+    Cancelled,
+    Timeout(String),
     Other(String),
 }
 
@@ -10,6 +12,8 @@ impl std::fmt::Display for LoginError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             LoginError::AlreadyLoggedIn => write!(f, "Already logged in"),
+            LoginError::Cancelled => write!(f, "Cancelled"),
+            LoginError::Timeout(msg) => write!(f, "Timed out: {}", msg),
             LoginError::Other(msg) => write!(f, "{}", msg),
         }
     }
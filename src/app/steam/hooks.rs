@@ -0,0 +1,98 @@
+use std::{fmt, process::{Command, Stdio}, time::Duration};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Hooks
+///
+/// Shell commands run around a game launch, empty commands are skipped
+///
+/// # Variables
+///
+/// - `pre_launch` - Run before Steam is switched, a non zero exit code aborts the launch
+/// - `post_exit` - Run once the game exits, failures are only reported
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Hooks {
+    pub pre_launch: String,
+    pub post_exit: String,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_launch.trim().is_empty() && self.post_exit.trim().is_empty()
+    }
+
+    pub fn command(&self, kind: HookKind) -> Option<&str> {
+        let command = match kind {
+            HookKind::PreLaunch => &self.pre_launch,
+            HookKind::PostExit => &self.post_exit,
+        };
+        Some(command.trim()).filter(|x| !x.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    PreLaunch,
+    PostExit,
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookKind::PreLaunch => write!(f, "pre-launch"),
+            HookKind::PostExit => write!(f, "post-exit"),
+        }
+    }
+}
+
+/// Build the shell command a hook runs in
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    let mut shell = Command::new("cmd");
+    // Passed as is, cmd does its own parsing of quotes
+    shell.arg("/C").raw_arg(command).creation_flags(CREATE_NO_WINDOW);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+/// Run a hook command with the provided environment variables and wait for it to exit
+///
+/// `cancelled` is checked while waiting, the hook is killed once it returns `true`.
+/// Returns an error if the command could not be started or exited with a non zero code
+pub fn run_hook(command: &str, env: &[(String, String)], cancelled: impl Fn() -> bool) -> Result<()> {
+    log::info!("Running hook: {}", command);
+    let mut child = shell(command)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            if status.success() {
+                return Ok(());
+            }
+            return Err(match status.code() {
+                Some(code) => format!("\"{}\" exited with code {}", command, code).into(),
+                None => format!("\"{}\" was terminated", command).into(),
+            });
+        }
+        if cancelled() {
+            let _ = child.kill();
+            return Err(format!("\"{}\" was cancelled", command).into());
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,206 @@
+use std::{fs, path::{Path, PathBuf}, sync::Mutex, time::{SystemTime, UNIX_EPOCH}};
+
+use super::backup::backups_root;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Serializes journal writes, the launch pipeline records changes from its own thread
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// A registry value as it was before a change
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryValue {
+    String(String),
+    U32(u32),
+}
+
+impl std::fmt::Display for RegistryValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryValue::String(value) => write!(f, "\"{}\"", value),
+            RegistryValue::U32(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// What a journal entry can undo
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// A value in the Steam registry key, `previous` is `None` if it didn't exist
+    Registry { name: String, previous: Option<RegistryValue> },
+    /// A file, `backup` is `None` if it didn't exist
+    File { path: PathBuf, backup: Option<PathBuf> },
+}
+
+/// Journal Entry
+///
+/// A change wait made to the Steam config, recorded right before it was made
+///
+/// # Variables
+///
+/// - `id` - Unique id of the entry
+/// - `timestamp` - Unix timestamp of the change
+/// - `description` - What the change was made for
+/// - `change` - What was changed and its previous state
+/// - `restored` - Unix timestamp of when the previous state was restored
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub timestamp: u64,
+    pub description: String,
+    pub change: Change,
+    pub restored: Option<u64>,
+}
+
+impl JournalEntry {
+    /// Describe what was changed, e.g. `AutoLoginUser (was "main")`
+    pub fn target(&self) -> String {
+        match &self.change {
+            Change::Registry { name, previous: Some(previous) } => format!("{} (was {})", name, previous),
+            Change::Registry { name, previous: None } => format!("{} (didn't exist)", name),
+            Change::File { path, backup: Some(_) } => path.display().to_string(),
+            Change::File { path, backup: None } => format!("{} (didn't exist)", path.display()),
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default()
+}
+
+fn journal_path() -> PathBuf {
+    backups_root().join("journal.json")
+}
+
+/// The folder copies of changed files are kept in
+fn files_dir() -> PathBuf {
+    backups_root().join("journal")
+}
+
+/// Load all journal entries, oldest first
+///
+/// A missing or unreadable journal is shown as empty
+pub fn load() -> Vec<JournalEntry> {
+    read().unwrap_or_else(|e| {
+        log::warn!("Failed to read the change journal: {}", e);
+        Vec::new()
+    })
+}
+
+/// Read all journal entries, only a missing journal counts as empty
+fn read() -> Result<Vec<JournalEntry>> {
+    let text = match fs::read_to_string(journal_path()) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Read all journal entries before changing them
+///
+/// A journal that can't be parsed is renamed aside instead of being overwritten, so the entries and copies it points to aren't lost
+fn read_for_write() -> Result<Vec<JournalEntry>> {
+    match read() {
+        Ok(entries) => Ok(entries),
+        Err(e) if e.is::<serde_json::Error>() => {
+            let mut aside = journal_path().into_os_string();
+            aside.push(format!(".corrupt-{}", now()));
+            fs::rename(journal_path(), &aside)?;
+            log::warn!("The change journal could not be parsed ({}), it was moved to {:?}", e, aside);
+            Ok(Vec::new())
+        },
+        Err(e) => Err(e),
+    }
+}
+
+fn save(entries: &[JournalEntry]) -> Result<()> {
+    let path = journal_path();
+    fs::create_dir_all(backups_root())?;
+
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, serde_json::to_string_pretty(entries)?)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+fn append(description: &str, change: impl FnOnce(u64) -> Result<Change>) -> Result<()> {
+    let _lock = LOCK.lock().unwrap_or_else(|x| x.into_inner());
+    let mut entries = read_for_write()?;
+    let id = entries.iter().map(|x| x.id + 1).max().unwrap_or_default();
+
+    entries.push(JournalEntry {
+        id,
+        timestamp: now(),
+        description: description.to_string(),
+        change: change(id)?,
+        restored: None,
+    });
+    save(&entries)
+}
+
+/// Record the previous state of a registry value
+pub fn record_registry(name: &str, previous: Option<RegistryValue>, description: &str) -> Result<()> {
+    append(description, |_| Ok(Change::Registry { name: name.to_string(), previous }))
+}
+
+/// Record the previous state of a file, copying it into the journal folder
+pub fn record_file(path: &Path, description: &str) -> Result<()> {
+    append(description, |id| {
+        if !path.exists() {
+            return Ok(Change::File { path: path.to_path_buf(), backup: None });
+        }
+
+        let name = path.file_name().map(|x| x.to_string_lossy().to_string()).unwrap_or_default();
+        let backup = files_dir().join(format!("{}-{}", id, name));
+        fs::create_dir_all(files_dir())?;
+        fs::copy(path, &backup)?;
+        Ok(Change::File { path: path.to_path_buf(), backup: Some(backup) })
+    })
+}
+
+/// Mark an entry as restored
+pub fn mark_restored(id: u64) -> Result<()> {
+    let _lock = LOCK.lock().unwrap_or_else(|x| x.into_inner());
+    let mut entries = read_for_write()?;
+    if let Some(entry) = entries.iter_mut().find(|x| x.id == id) {
+        entry.restored = Some(now());
+    }
+    save(&entries)
+}
+
+/// Remove journal entries older than `max_age_days` and the file copies they own
+///
+/// Other folders in the backups folder, like archived userdata, are never touched.
+/// Returns the number of entries removed
+pub fn prune(max_age_days: u64) -> Result<usize> {
+    let _lock = LOCK.lock().unwrap_or_else(|x| x.into_inner());
+    let cutoff = now().saturating_sub(max_age_days * 86400);
+
+    let (old, kept): (Vec<JournalEntry>, Vec<JournalEntry>) = read_for_write()?.into_iter().partition(|x| x.timestamp < cutoff);
+    if old.is_empty() {
+        return Ok(0);
+    }
+
+    for entry in &old {
+        if let Change::File { backup: Some(backup), .. } = &entry.change {
+            // Only remove copies inside the journal folder
+            if !backup.starts_with(files_dir()) {
+                continue;
+            }
+            if let Err(e) = fs::remove_file(backup) {
+                log::warn!("Failed to remove {:?}: {}", backup, e);
+            }
+        }
+    }
+    save(&kept)?;
+
+    Ok(old.len())
+}
@@ -0,0 +1,700 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::PathBuf,
+    sync::{atomic::{AtomicBool, Ordering}, mpsc::{channel, Receiver, Sender, TryRecvError}, Arc, Mutex, MutexGuard, TryLockError},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+use sysinfo::{Pid, System};
+
+use super::{error::LoginError, hooks::{self, HookKind, Hooks}, process, AppID, SteamModel};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Held while a pipeline stops, switches or starts Steam, so launches and exit watches don't restart it at the same time
+static STEAM: Mutex<()> = Mutex::new(());
+
+/// Exit watches still running, joined by [`join_watches`] once the window is closed
+static WATCHES: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Launch Stage
+///
+/// The stages a launch pipeline walks through, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchStage {
+    StoppingSteam,
+    WaitingForSteam,
+    SwitchingAccount,
+    StartingSteam,
+    WaitingForLogin,
+    GameStarted,
+    WaitingForGame,
+    GameRunning,
+    WaitingForOtherGames,
+    RestoringAccount,
+    RunningHook(HookKind),
+}
+
+impl fmt::Display for LaunchStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchStage::StoppingSteam => write!(f, "Stopping Steam"),
+            LaunchStage::WaitingForSteam => write!(f, "Waiting for Steam to close"),
+            LaunchStage::SwitchingAccount => write!(f, "Switching account"),
+            LaunchStage::StartingSteam => write!(f, "Starting Steam"),
+            LaunchStage::WaitingForLogin => write!(f, "Waiting for Steam to log in"),
+            LaunchStage::GameStarted => write!(f, "Game started"),
+            LaunchStage::WaitingForGame => write!(f, "Waiting for the game to start"),
+            LaunchStage::GameRunning => write!(f, "Game running"),
+            LaunchStage::WaitingForOtherGames => write!(f, "Waiting for other games to exit"),
+            LaunchStage::RestoringAccount => write!(f, "Switching back account"),
+            LaunchStage::RunningHook(kind) => write!(f, "Running {} hook", kind),
+        }
+    }
+}
+
+/// Launch Event
+///
+/// Events sent from a running pipeline to the UI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchEvent {
+    Stage(LaunchStage),
+    /// Something went wrong that doesn't stop the pipeline
+    Warning(String),
+    /// A step a dry run would have taken
+    DryRun(String),
+    Finished,
+    Failed(String),
+    Cancelled,
+}
+
+/// Start Mode
+///
+/// How Steam presents an account once it is logged in
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StartMode {
+    #[default]
+    Online,
+    Offline,
+    Invisible,
+}
+
+impl fmt::Display for StartMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartMode::Online => write!(f, "Online"),
+            StartMode::Offline => write!(f, "Offline"),
+            StartMode::Invisible => write!(f, "Invisible"),
+        }
+    }
+}
+
+impl StartMode {
+    pub const ALL: [StartMode; 3] = [StartMode::Online, StartMode::Offline, StartMode::Invisible];
+}
+
+/// Launch Config
+///
+/// User configurable behaviour of the launch pipeline
+///
+/// # Variables
+///
+/// - `close_timeout` - Seconds to wait for Steam to close before giving up
+/// - `force_kill` - Kill the Steam process once `close_timeout` has passed instead of failing
+/// - `login_timeout` - Seconds to wait for Steam to log into the account before launching anyway
+/// - `game_timeout` - Seconds to wait for a game process to appear before giving up on switching back
+/// - `start_modes` - The start mode of each account, accounts that aren't listed start online
+/// - `hooks` - Hooks run around every game launch
+/// - `game_hooks` - Hooks of each game, run after the global hooks
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct LaunchConfig {
+    pub close_timeout: u64,
+    pub force_kill: bool,
+    pub login_timeout: u64,
+    pub game_timeout: u64,
+    pub start_modes: HashMap<String, StartMode>,
+    pub hooks: Hooks,
+    pub game_hooks: HashMap<i32, Hooks>,
+}
+
+impl LaunchConfig {
+    pub fn start_mode(&self, account: &str) -> StartMode {
+        self.start_modes.get(account).copied().unwrap_or_default()
+    }
+
+    /// Get the hook commands of a kind for a game, the global hook first
+    pub fn hook_commands(&self, appid: i32, kind: HookKind) -> Vec<String> {
+        [Some(&self.hooks), self.game_hooks.get(&appid)]
+            .into_iter()
+            .flatten()
+            .filter_map(|x| x.command(kind))
+            .map(|x| x.to_string())
+            .collect()
+    }
+}
+
+impl Default for LaunchConfig {
+    fn default() -> Self {
+        Self {
+            close_timeout: 30,
+            force_kill: false,
+            login_timeout: 120,
+            game_timeout: 120,
+            start_modes: HashMap::new(),
+            hooks: Hooks::default(),
+            game_hooks: HashMap::new(),
+        }
+    }
+}
+
+/// Launch Request
+///
+/// What a launch pipeline should do
+///
+/// # Variables
+///
+/// - `account` - The account to log into
+/// - `args` - Arguments Steam is started with
+/// - `url` - A `steam://` url dispatched to Steam once it is logged in
+/// - `appid` - The game being launched, if any
+/// - `game_args` - Arguments of the game, the game is started with `-applaunch` instead of `url` if there are any
+/// - `env` - Environment variables Steam is started with and the game inherits, Steam is always restarted to apply them
+/// - `restore` - The account to switch back to once the game exits
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LaunchRequest {
+    pub account: String,
+    pub args: Vec<String>,
+    pub url: Option<String>,
+    pub appid: Option<i32>,
+    pub game_args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub restore: Option<String>,
+}
+
+/// Launch Handle
+///
+/// The UI side of a running pipeline, used to poll its progress and cancel it
+pub struct LaunchHandle {
+    pub account: String,
+    pub dry_run: bool,
+    pub url: Option<String>,
+    pub appid: Option<i32>,
+    pub restore: Option<String>,
+    pub stage: Option<LaunchStage>,
+    receiver: Receiver<LaunchEvent>,
+    cancel: Arc<AtomicBool>,
+    done: bool,
+}
+
+impl LaunchHandle {
+    /// Request the pipeline to stop at the next opportunity
+    ///
+    /// Steam can not be stopped from starting once it has been spawned
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Collect all events sent since the last poll
+    ///
+    /// A pipeline that stopped without finishing, e.g. because its thread panicked, is reported as failed
+    pub fn poll(&mut self) -> Vec<LaunchEvent> {
+        let mut events = Vec::new();
+        while !self.done {
+            let event = match self.receiver.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => LaunchEvent::Failed("The launch pipeline stopped unexpectedly".to_string()),
+            };
+            match &event {
+                LaunchEvent::Stage(stage) => self.stage = Some(stage.clone()),
+                LaunchEvent::Finished | LaunchEvent::Failed(_) | LaunchEvent::Cancelled => self.done = true,
+                _ => {},
+            }
+            events.push(event);
+        }
+        events
+    }
+}
+
+struct Pipeline {
+    model: SteamModel,
+    request: LaunchRequest,
+    config: LaunchConfig,
+    sender: Sender<LaunchEvent>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Pipeline {
+    fn steam_exe(&self) -> PathBuf {
+        self.model.install_path.join("steam.exe")
+    }
+
+    fn stage(&self, stage: LaunchStage) {
+        log::info!("Launch pipeline: {}", stage);
+        let _ = self.sender.send(LaunchEvent::Stage(stage));
+    }
+
+    fn check_cancel(&self) -> Result<()> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(Box::new(LoginError::Cancelled));
+        }
+        Ok(())
+    }
+
+    /// Wait for other pipelines to be done with Steam
+    fn lock_steam(&self) -> Result<MutexGuard<'static, ()>> {
+        loop {
+            match STEAM.try_lock() {
+                Ok(guard) => return Ok(guard),
+                Err(TryLockError::Poisoned(e)) => return Ok(e.into_inner()),
+                Err(TryLockError::WouldBlock) => {},
+            }
+            self.check_cancel()?;
+            std::thread::sleep(Duration::from_millis(200));
+        }
+    }
+
+    fn run(self, entry: fn(&Pipeline) -> Result<()>) {
+        let event = match entry(&self) {
+            Ok(_) => LaunchEvent::Finished,
+            Err(e) => match e.downcast_ref::<LoginError>() {
+                Some(LoginError::Cancelled) => LaunchEvent::Cancelled,
+                _ => {
+                    log::error!("Launch pipeline failed: {}", e);
+                    LaunchEvent::Failed(e.to_string())
+                },
+            },
+        };
+        let _ = self.sender.send(event);
+    }
+
+    fn launch(&self) -> Result<()> {
+        let _steam = self.lock_steam()?;
+        self.execute()
+    }
+
+    /// Run the launch, in a dry run every step that would change something is only reported
+    fn execute(&self) -> Result<()> {
+        if let Some(appid) = self.request.appid {
+            self.run_hooks(appid, HookKind::PreLaunch)?;
+            self.check_cancel()?;
+        }
+
+        let mode = self.config.start_mode(&self.request.account);
+        // Environment variables only reach the game through a freshly started Steam
+        let restart = self.model.restarts_steam(&self.request.account, mode, &self.request.env);
+        if restart {
+            self.stop_steam()?;
+            self.check_cancel()?;
+
+            self.stage(LaunchStage::SwitchingAccount);
+            self.switch_account(&self.request.account)?;
+            self.check_cancel()?;
+        }
+
+        if restart || !self.model.is_steam_running() {
+            self.stage(LaunchStage::StartingSteam);
+            self.start_steam(&self.request.env)?;
+        }
+
+        if self.request.url.is_none() && mode != StartMode::Invisible {
+            return Ok(());
+        }
+
+        let logged_in = self.wait_for_login(&self.request.account)?;
+        if mode == StartMode::Invisible {
+            self.set_invisible(logged_in)?;
+        }
+
+        let Some(url) = &self.request.url else {
+            return Ok(());
+        };
+
+        if logged_in && self.request.game_args.is_empty() {
+            log::info!("Dispatching {}", url);
+            self.send_to_steam(std::slice::from_ref(url))?;
+        } else if let Some(appid) = self.request.appid {
+            // Steam never reported the login, hand the launch to the client and hope for the best
+            if !logged_in {
+                log::warn!("Steam did not log in within {} seconds, falling back to -applaunch", self.config.login_timeout);
+            }
+            // Game arguments are passed to steam.exe as they are, in a url they would have to be escaped
+            let args: Vec<String> = ["-applaunch".to_string(), appid.to_string()].into_iter().chain(self.request.game_args.clone()).collect();
+            self.send_to_steam(&args)?;
+        } else {
+            log::warn!("Steam did not log in within {} seconds, dispatching {} anyway", self.config.login_timeout, url);
+            self.send_to_steam(std::slice::from_ref(url))?;
+        }
+
+        if self.request.appid.is_some() {
+            self.stage(LaunchStage::GameStarted);
+        }
+
+        Ok(())
+    }
+
+    /// Follow a launched game until it exits, then run the post-exit hooks and switch Steam back to the `restore` account
+    ///
+    /// Runs separately from the launch, so other launches aren't blocked while the game runs
+    fn watch(&self) -> Result<()> {
+        let Some(appid) = self.request.appid else {
+            return Ok(());
+        };
+        let exited = self.wait_for_exit(appid);
+        self.check_cancel()?;
+
+        // Hooks clean up after the pre-launch hooks, so they run even if the game never showed up
+        self.run_hooks(appid, HookKind::PostExit)?;
+        exited?;
+
+        if let Some(restore) = &self.request.restore {
+            self.wait_for_other_games(appid)?;
+            let _steam = self.lock_steam()?;
+            self.restore_account(restore)?;
+        }
+
+        Ok(())
+    }
+
+    /// Wait until no other game is running, restarting Steam would close them
+    fn wait_for_other_games(&self, appid: i32) -> Result<()> {
+        let others: Vec<&AppID> = self.model.games.keys().filter(|x| x.id != appid).collect();
+        let mut system = System::new();
+        let mut waiting = false;
+
+        loop {
+            self.check_cancel()?;
+            process::refresh(&mut system);
+            let running = !process::running_games(&system, others.iter().copied()).is_empty()
+                || self.model.running_app_id().is_some_and(|x| x != appid);
+            if !running {
+                return Ok(());
+            }
+            if self.model.dry_run {
+                self.would("Wait for the other running games to exit".to_string());
+                return Ok(());
+            }
+            if !waiting {
+                self.stage(LaunchStage::WaitingForOtherGames);
+                waiting = true;
+            }
+            std::thread::sleep(Duration::from_secs(2));
+        }
+    }
+
+    /// Report a step of a dry run
+    fn would(&self, step: String) {
+        log::info!("Dry run: {}", step);
+        let _ = self.sender.send(LaunchEvent::DryRun(step));
+    }
+
+    /// Start Steam with the arguments of the request and `env`
+    fn start_steam(&self, env: &[(String, String)]) -> Result<()> {
+        let args = &self.request.args;
+        if self.model.dry_run {
+            let mut step = format!("Start {} {}", self.steam_exe().display(), args.join(" "));
+            if !env.is_empty() {
+                let env: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                step.push_str(&format!(" with {}", env.join(" ")));
+            }
+            self.would(step);
+            return Ok(());
+        }
+
+        log::info!("Starting Steam with args: {:?}", args);
+        steam_command!(self.steam_exe(), args)
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .spawn()?;
+        Ok(())
+    }
+
+    /// Hand a url or other arguments to the running client
+    fn send_to_steam(&self, args: &[String]) -> Result<()> {
+        if self.model.dry_run {
+            self.would(format!("Run {} {}", self.steam_exe().display(), args.join(" ")));
+            return Ok(());
+        }
+        steam_command!(self.steam_exe(), args).spawn()?;
+        Ok(())
+    }
+
+    fn game(&self, appid: i32) -> Result<&AppID> {
+        match self.model.games.keys().find(|x| x.id == appid) {
+            Some(app) => Ok(app),
+            None => Err(Box::new(LoginError::Other(format!("Game {} is not installed", appid)))),
+        }
+    }
+
+    /// Environment variables passed to hooks
+    fn hook_env(&self, appid: i32, kind: HookKind) -> Vec<(String, String)> {
+        let app = self.game(appid).ok();
+        vec![
+            ("WAIT_HOOK".to_string(), kind.to_string()),
+            ("WAIT_APPID".to_string(), appid.to_string()),
+            ("WAIT_ACCOUNT".to_string(), self.request.account.clone()),
+            ("WAIT_RESTORE_ACCOUNT".to_string(), self.request.restore.clone().unwrap_or_default()),
+            ("WAIT_GAME_NAME".to_string(), app.map(|x| x.name.clone()).unwrap_or_default()),
+            ("WAIT_INSTALL_PATH".to_string(), app.map(|x| x.location.to_string_lossy().to_string()).unwrap_or_default()),
+            ("WAIT_STEAM_PATH".to_string(), self.model.install_path.to_string_lossy().to_string()),
+        ]
+    }
+
+    /// Run the global and game hooks of a kind
+    ///
+    /// A failing pre-launch hook aborts the launch, post-exit failures are sent as warnings
+    fn run_hooks(&self, appid: i32, kind: HookKind) -> Result<()> {
+        let commands = self.config.hook_commands(appid, kind);
+        if commands.is_empty() {
+            return Ok(());
+        }
+
+        self.stage(LaunchStage::RunningHook(kind));
+        let env = self.hook_env(appid, kind);
+        for command in commands {
+            if self.model.dry_run {
+                self.would(format!("Run the {} hook: {}", kind, command));
+                continue;
+            }
+            if let Err(e) = hooks::run_hook(&command, &env, || self.cancel.load(Ordering::Relaxed)) {
+                self.check_cancel()?;
+                log::error!("The {} hook failed: {}", kind, e);
+                match kind {
+                    HookKind::PreLaunch => return Err(Box::new(LoginError::Other(format!("The {} hook failed: {}", kind, e)))),
+                    HookKind::PostExit => {
+                        let _ = self.sender.send(LaunchEvent::Warning(format!("The {} hook failed: {}", kind, e)));
+                    },
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn switch_account(&self, account: &String) -> Result<()> {
+        let mode = self.config.start_mode(account);
+        match self.model.set_login_account(account, mode) {
+            Ok(_) => {
+                if self.model.dry_run {
+                    for change in self.model.login_changes(account, mode) {
+                        self.would(change);
+                    }
+                }
+                Ok(())
+            },
+            Err(e) if e.downcast_ref::<LoginError>() == Some(&LoginError::AlreadyLoggedIn) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Set the friends status of the logged in account to invisible
+    fn set_invisible(&self, logged_in: bool) -> Result<()> {
+        if !logged_in {
+            log::warn!("Steam did not log in within {} seconds, not going invisible", self.config.login_timeout);
+            return Ok(());
+        }
+        log::info!("Going invisible");
+        self.send_to_steam(&["steam://friends/status/invisible".to_string()])
+    }
+
+    /// Wait for the game to start and exit again
+    fn wait_for_exit(&self, appid: i32) -> Result<()> {
+        let app = self.game(appid)?;
+        let mut system = System::new();
+
+        self.stage(LaunchStage::WaitingForGame);
+        if self.model.dry_run {
+            self.would(format!("Wait for {} to start and exit", app.name));
+            return Ok(());
+        }
+        let timeout = Duration::from_secs(self.config.game_timeout);
+        let started = Instant::now();
+        loop {
+            self.check_cancel()?;
+            process::refresh(&mut system);
+            if process::running_games(&system, [app]).contains(&appid) {
+                break;
+            }
+            if started.elapsed() > timeout {
+                return Err(Box::new(LoginError::Timeout(format!("{} did not start within {} seconds", app.name, timeout.as_secs()))));
+            }
+            std::thread::sleep(Duration::from_secs(1));
+        }
+
+        self.stage(LaunchStage::GameRunning);
+        while process::running_games(&system, [app]).contains(&appid) {
+            self.check_cancel()?;
+            std::thread::sleep(Duration::from_secs(2));
+            process::refresh(&mut system);
+        }
+        log::info!("{} exited", app.name);
+
+        Ok(())
+    }
+
+    /// Switch Steam back to the `restore` account
+    fn restore_account(&self, restore: &String) -> Result<()> {
+        self.stage(LaunchStage::RestoringAccount);
+        self.stop_steam()?;
+        self.switch_account(restore)?;
+
+        self.stage(LaunchStage::StartingSteam);
+        self.start_steam(&[])?;
+
+        if self.config.start_mode(restore) == StartMode::Invisible {
+            let logged_in = self.wait_for_login(restore)?;
+            self.set_invisible(logged_in)?;
+        }
+
+        Ok(())
+    }
+
+    /// Wait until the running client reports being logged in as `account`
+    ///
+    /// Returns `false` if `login_timeout` passed first
+    fn wait_for_login(&self, account: &str) -> Result<bool> {
+        let account_id = self.model.user_cache
+            .iter()
+            .find(|x| x.name == account)
+            .and_then(|x| x.id.as_ref())
+            .map(|id| id.account_id);
+
+        self.stage(LaunchStage::WaitingForLogin);
+        if self.model.dry_run {
+            // Nothing was started, assume the login works
+            self.would(format!("Wait for '{}' to log in", account));
+            return Ok(true);
+        }
+        let timeout = Duration::from_secs(self.config.login_timeout);
+        let started = Instant::now();
+        while started.elapsed() < timeout {
+            self.check_cancel()?;
+
+            if let Some((active_user, pid)) = self.model.active_process() {
+                let logged_in = match account_id {
+                    Some(account_id) => active_user == account_id,
+                    None => active_user != 0,
+                };
+                let running = System::new_all().process(Pid::from_u32(pid)).is_some();
+
+                if logged_in && running {
+                    // Give the client a moment to finish loading before handing it a command
+                    std::thread::sleep(Duration::from_secs(2));
+                    return Ok(true);
+                }
+            }
+
+            std::thread::sleep(Duration::from_millis(500));
+        }
+
+        Ok(false)
+    }
+
+    fn stop_steam(&self) -> Result<()> {
+        let mut system = System::new_all();
+        if system.processes_by_exact_name("steam.exe".as_ref()).count() == 0 {
+            return Ok(());
+        }
+
+        self.stage(LaunchStage::StoppingSteam);
+        if self.model.dry_run {
+            self.would(format!("Close Steam with {} -exitsteam", self.steam_exe().display()));
+            return Ok(());
+        }
+        steam_command!(self.steam_exe(), ["-exitsteam"]).output()?;
+
+        self.stage(LaunchStage::WaitingForSteam);
+        let timeout = Duration::from_secs(self.config.close_timeout);
+        let started = Instant::now();
+        let mut killed = false;
+        while system.processes_by_exact_name("steam.exe".as_ref()).count() > 0 {
+            self.check_cancel()?;
+
+            if started.elapsed() > timeout && !killed {
+                if !self.config.force_kill {
+                    return Err(Box::new(LoginError::Timeout(format!("Steam did not close within {} seconds", timeout.as_secs()))));
+                }
+                log::warn!("Steam did not close within {} seconds, killing...", timeout.as_secs());
+                for process in system.processes_by_exact_name("steam.exe".as_ref()) {
+                    process.kill();
+                }
+                killed = true;
+            } else if killed && started.elapsed() > timeout * 2 {
+                return Err(Box::new(LoginError::Timeout("Steam could not be killed".to_string())));
+            }
+
+            std::thread::sleep(Duration::from_millis(500));
+            system = System::new_all(); // system.refresh_all() just doesn't work ig ¯\_(ツ)_/¯
+        }
+        log::info!("Steam closed");
+
+        Ok(())
+    }
+}
+
+/// Spawn a launch pipeline on a new thread
+///
+/// Returns a [`LaunchHandle`] to follow its progress
+pub(super) fn spawn(model: SteamModel, request: LaunchRequest, config: LaunchConfig) -> LaunchHandle {
+    let (handle, pipeline) = pipeline(model, request, config);
+    std::thread::spawn(move || pipeline.run(Pipeline::launch));
+    handle
+}
+
+/// Spawn a thread that waits for the game of `request` to exit, runs the post-exit hooks and switches back to its `restore` account
+///
+/// Returns a [`LaunchHandle`] to follow its progress, the thread keeps running after the window is closed
+pub(super) fn spawn_watch(model: SteamModel, request: LaunchRequest, config: LaunchConfig) -> LaunchHandle {
+    let (handle, pipeline) = pipeline(model, request, config);
+    let thread = std::thread::spawn(move || pipeline.run(Pipeline::watch));
+
+    let mut watches = WATCHES.lock().unwrap_or_else(|x| x.into_inner());
+    watches.retain(|x| !x.is_finished());
+    watches.push(thread);
+
+    handle
+}
+
+/// Wait for all exit watches to finish
+///
+/// Called once the window is closed, so switching back still happens when wait closes after a launch
+pub fn join_watches() {
+    let watches: Vec<JoinHandle<()>> = std::mem::take(&mut *WATCHES.lock().unwrap_or_else(|x| x.into_inner()));
+    let running = watches.iter().filter(|x| !x.is_finished()).count();
+    if running > 0 {
+        log::info!("Waiting for {} games to exit before closing", running);
+    }
+    for watch in watches {
+        let _ = watch.join();
+    }
+}
+
+fn pipeline(model: SteamModel, request: LaunchRequest, config: LaunchConfig) -> (LaunchHandle, Pipeline) {
+    let (sender, receiver) = channel();
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    let handle = LaunchHandle {
+        account: request.account.clone(),
+        dry_run: model.dry_run,
+        url: request.url.clone(),
+        appid: request.appid,
+        restore: request.restore.clone(),
+        stage: None,
+        receiver,
+        cancel: cancel.clone(),
+        done: false,
+    };
+
+    let pipeline = Pipeline {
+        model,
+        request,
+        config,
+        sender,
+        cancel,
+    };
+
+    (handle, pipeline)
+}
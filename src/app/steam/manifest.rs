@@ -4,6 +4,7 @@ use std::iter::Peekable;
 pub(crate) mod prelude {
     pub(crate) use super::ManifestParseError;
     pub(crate) use super::parse_manifest;
+    pub(crate) use super::get_path;
 }
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -29,15 +30,26 @@ fn skip_whitespace(input: &mut Peekable<Chars>) {
     }
 }
 
+/// Parse a quoted string, unescaping `\"` and `\\` like Steam writes them
+/// 
+/// Other escape sequences are kept as is
 fn parse_string(input: &mut Peekable<Chars>) -> String {
     let mut value = String::new();
     input.next(); // Consume the opening double quote
 
     while let Some(ch) = input.next() {
-        if ch == '"' {
-            break;
+        match ch {
+            '"' => break,
+            '\\' => match input.next() {
+                Some(escaped @ ('"' | '\\')) => value.push(escaped),
+                Some(escaped) => {
+                    value.push(ch);
+                    value.push(escaped);
+                },
+                None => value.push(ch),
+            },
+            _ => value.push(ch),
         }
-        value.push(ch);
     }
 
     value
@@ -88,6 +100,10 @@ fn parse_object(input: &mut Peekable<Chars>) -> serde_json::Value {
 
 pub fn parse_manifest(path: std::path::PathBuf) -> Result<serde_json::Value> {
     let input = std::fs::read_to_string(path)?;
+    Ok(parse_text(&input))
+}
+
+fn parse_text(input: &str) -> serde_json::Value {
     let mut input = input.chars().peekable();
 
     // Skip first line
@@ -106,5 +122,322 @@ pub fn parse_manifest(path: std::path::PathBuf) -> Result<serde_json::Value> {
     }
     input.next();
 
-    Ok(parse_object(&mut input))
-}
\ No newline at end of file
+    parse_object(&mut input)
+}
+
+/// Follow a path of keys through a parsed manifest
+/// 
+/// Keys are matched case-insensitively, Steam is not consistent about `Valve`/`valve`
+pub fn get_path<'a>(value: &'a serde_json::Value, path: &[&str]) -> Option<&'a serde_json::Value> {
+    path.iter().try_fold(value, |value, key| {
+        value.as_object()?
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    })
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    /// Byte range of a quoted string, without the quotes
+    String(usize, usize),
+    Open(usize),
+    Close(usize),
+}
+
+/// Split a manifest into strings and braces, keeping the byte positions of each
+fn tokenize(input: &str) -> Vec<Token> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let start = i + 1;
+                i = start;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1; // Skip the escaped character
+                    }
+                    i += 1;
+                }
+                tokens.push(Token::String(start, i.min(bytes.len())));
+            },
+            b'{' => tokens.push(Token::Open(i)),
+            b'}' => tokens.push(Token::Close(i)),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            },
+            _ => {},
+        }
+        i += 1;
+    }
+
+    tokens
+}
+
+/// Escape a value the way Steam writes it
+pub fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Find the value of `path` in a manifest
+/// 
+/// Returns the byte range of the value if it exists,
+/// otherwise where a new key of the block should be inserted and the depth of that block
+fn locate(input: &str, path: &[&str]) -> Option<std::result::Result<(usize, usize), (usize, usize)>> {
+    let (key, block) = path.split_last()?;
+    let tokens = tokenize(input);
+    let mut stack: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    let in_block = |stack: &Vec<&str>| stack.len() == block.len() && stack.iter().zip(block).all(|(a, b)| a.eq_ignore_ascii_case(b));
+
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::String(start, end) => {
+                let name = &input[start..end];
+                match tokens.get(i + 1) {
+                    Some(Token::Open(_)) => {
+                        stack.push(name);
+                        i += 1;
+                    },
+                    Some(Token::String(value_start, value_end)) => {
+                        if in_block(&stack) && name.eq_ignore_ascii_case(key) {
+                            return Some(Ok((*value_start, *value_end)));
+                        }
+                        i += 1;
+                    },
+                    _ => {},
+                }
+            },
+            Token::Close(pos) => {
+                if in_block(&stack) {
+                    let line_start = input[..pos].rfind('\n').map(|x| x + 1).unwrap_or(0);
+                    return Some(Err((line_start, stack.len())));
+                }
+                stack.pop();
+            },
+            Token::Open(_) => {},
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Set the value at `path` in the text of a manifest, leaving the rest of the file untouched
+/// 
+/// The key is added to its block if it is missing, the block itself has to exist.
+/// `value` is written as is, use [`escape`] for arbitrary text
+pub fn set_value(input: &str, path: &[&str], value: &str) -> Option<String> {
+    let mut output = input.to_string();
+
+    match locate(input, path)? {
+        Ok((start, end)) => output.replace_range(start..end, value),
+        Err((line_start, depth)) => {
+            let line = format!("{}\"{}\"\t\t\"{}\"\n", "\t".repeat(depth), path.last()?, value);
+            output.insert_str(line_start, &line);
+        },
+    }
+
+    Some(output)
+}
+
+/// Get the raw value at `path` in the text of a manifest
+pub fn get_value<'a>(input: &'a str, path: &[&str]) -> Option<&'a str> {
+    match locate(input, path)? {
+        Ok((start, end)) => Some(&input[start..end]),
+        Err(_) => None,
+    }
+}
+
+/// Remove the key at `path` from the text of a manifest, including its whole block if it has one
+/// 
+/// Returns `None` if the key doesn't exist
+pub fn remove_key(input: &str, path: &[&str]) -> Option<String> {
+    let (key, block) = path.split_last()?;
+    let tokens = tokenize(input);
+    let mut stack: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    let line_start = |pos: usize| input[..pos].rfind('\n').map(|x| x + 1).unwrap_or(0);
+    let line_end = |pos: usize| input[pos..].find('\n').map(|x| pos + x + 1).unwrap_or(input.len());
+
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::String(start, end) => {
+                let name = &input[start..end];
+                let in_block = stack.len() == block.len() && stack.iter().zip(block).all(|(a, b)| a.eq_ignore_ascii_case(b));
+                let matched = in_block && name.eq_ignore_ascii_case(key);
+
+                match tokens.get(i + 1) {
+                    Some(Token::Open(_)) if matched => {
+                        // Find the matching closing brace
+                        let mut depth = 0;
+                        for token in &tokens[i + 1..] {
+                            match token {
+                                Token::Open(_) => depth += 1,
+                                Token::Close(pos) => {
+                                    depth -= 1;
+                                    if depth == 0 {
+                                        let mut output = input.to_string();
+                                        output.replace_range(line_start(start)..line_end(*pos), "");
+                                        return Some(output);
+                                    }
+                                },
+                                Token::String(..) => {},
+                            }
+                        }
+                        return None;
+                    },
+                    Some(Token::Open(_)) => {
+                        stack.push(name);
+                        i += 1;
+                    },
+                    Some(Token::String(_, value_end)) => {
+                        if matched {
+                            let mut output = input.to_string();
+                            output.replace_range(line_start(start)..line_end(*value_end), "");
+                            return Some(output);
+                        }
+                        i += 1;
+                    },
+                    _ => {},
+                }
+            },
+            Token::Close(_) => {
+                stack.pop();
+            },
+            Token::Open(_) => {},
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Write the text of a manifest
+/// 
+/// The previous file is recorded in the change journal with `description` first
+pub fn write_manifest(path: &std::path::Path, text: &str, description: &str) -> Result<()> {
+    super::journal::record_file(path, description)?;
+
+    // Write to a temporary file first so a crash can't leave a half written manifest
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::fs::write(&temp, text)?;
+    std::fs::rename(&temp, path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\"UserLocalConfigStore\"\n{\n\t\"Software\"\n\t{\n\t\t\"Valve\"\n\t\t{\n\t\t\t\"Steam\"\n\t\t\t{\n\t\t\t\t\"apps\"\n\t\t\t\t{\n\t\t\t\t\t\"440\"\n\t\t\t\t\t{\n\t\t\t\t\t\t\"LaunchOptions\"\t\t\"-novid\"\n\t\t\t\t\t\t\"Playtime\"\t\t\"12\"\n\t\t\t\t\t}\n\t\t\t\t}\n\t\t\t}\n\t\t}\n\t}\n\t\"friends\"\n\t{\n\t\t\"PersonaName\"\t\t\"say \\\"hi\\\" C:\\\\\"\n\t}\n}\n";
+
+    const APP: [&str; 6] = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps", "440"];
+
+    fn app_key(key: &str) -> Vec<&str> {
+        APP.iter().copied().chain([key]).collect()
+    }
+
+    #[test]
+    fn set_value_only_touches_the_value() {
+        let output = set_value(CONFIG, &app_key("LaunchOptions"), "-novid -high").unwrap();
+        assert_eq!(output, CONFIG.replace("\"-novid\"", "\"-novid -high\""));
+        assert_eq!(set_value(CONFIG, &app_key("Playtime"), "12").unwrap(), CONFIG);
+    }
+
+    #[test]
+    fn escaped_values() {
+        assert_eq!(get_value(CONFIG, &["UserLocalConfigStore", "friends", "PersonaName"]), Some("say \\\"hi\\\" C:\\\\"));
+
+        let value = escape("-dx11 +exec \"my config.cfg\" C:\\games");
+        assert_eq!(value, "-dx11 +exec \\\"my config.cfg\\\" C:\\\\games");
+        let output = set_value(CONFIG, &app_key("LaunchOptions"), &value).unwrap();
+        assert_eq!(get_value(&output, &app_key("LaunchOptions")), Some(value.as_str()));
+        assert_eq!(get_value(&output, &app_key("Playtime")), Some("12"));
+    }
+
+    #[test]
+    fn inserts_missing_key_at_block_depth() {
+        let output = set_value(CONFIG, &app_key("cloud"), "1").unwrap();
+        let expected = CONFIG.replace(
+            "\t\t\t\t\t\t\"Playtime\"\t\t\"12\"\n",
+            "\t\t\t\t\t\t\"Playtime\"\t\t\"12\"\n\t\t\t\t\t\t\"cloud\"\t\t\"1\"\n",
+        );
+        assert_eq!(output, expected);
+        assert_eq!(get_value(&output, &app_key("cloud")), Some("1"));
+    }
+
+    #[test]
+    fn paths_ignore_case() {
+        let path = ["userlocalconfigstore", "software", "valve", "steam", "Apps", "440", "launchoptions"];
+        assert_eq!(get_value(CONFIG, &path), Some("-novid"));
+        assert_eq!(set_value(CONFIG, &path, "-console").unwrap(), CONFIG.replace("\"-novid\"", "\"-console\""));
+    }
+
+    #[test]
+    fn missing_block() {
+        let path = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps", "570", "LaunchOptions"];
+        assert_eq!(get_value(CONFIG, &path), None);
+        assert_eq!(set_value(CONFIG, &path, "-novid"), None);
+        assert_eq!(get_value(CONFIG, &app_key("cloud")), None);
+    }
+
+    #[test]
+    fn remove_nested_block() {
+        let output = remove_key(CONFIG, &APP).unwrap();
+        assert_eq!(output, CONFIG.replace(
+            "\t\t\t\t\t\"440\"\n\t\t\t\t\t{\n\t\t\t\t\t\t\"LaunchOptions\"\t\t\"-novid\"\n\t\t\t\t\t\t\"Playtime\"\t\t\"12\"\n\t\t\t\t\t}\n",
+            "",
+        ));
+        assert_eq!(get_value(&output, &app_key("LaunchOptions")), None);
+        assert_eq!(get_value(&output, &["UserLocalConfigStore", "friends", "PersonaName"]), Some("say \\\"hi\\\" C:\\\\"));
+    }
+
+    #[test]
+    fn remove_last_entry() {
+        let output = remove_key(CONFIG, &app_key("Playtime")).unwrap();
+        assert_eq!(output, CONFIG.replace("\t\t\t\t\t\t\"Playtime\"\t\t\"12\"\n", ""));
+        assert_eq!(get_value(&output, &app_key("LaunchOptions")), Some("-novid"));
+
+        let output = remove_key(&output, &app_key("LaunchOptions")).unwrap();
+        assert!(output.contains("\t\t\t\t\t\"440\"\n\t\t\t\t\t{\n\t\t\t\t\t}\n"));
+    }
+
+    #[test]
+    fn remove_missing_key() {
+        assert_eq!(remove_key(CONFIG, &app_key("cloud")), None);
+        assert_eq!(remove_key(CONFIG, &["UserLocalConfigStore", "Software", "Valve", "Steam", "apps", "570"]), None);
+    }
+
+    #[test]
+    fn parse_unescapes_strings() {
+        let parsed = parse_text(CONFIG);
+        assert_eq!(get_path(&parsed, &["friends", "PersonaName"]), Some(&serde_json::json!("say \"hi\" C:\\")));
+        assert_eq!(get_path(&parsed, &["Software", "Valve", "Steam", "apps", "440", "Playtime"]), Some(&serde_json::json!("12")));
+    }
+
+    #[test]
+    fn parse_library_paths() {
+        let text = "\"libraryfolders\"\n{\n\t\"0\"\n\t{\n\t\t\"path\"\t\t\"C:\\\\Program Files (x86)\\\\Steam\"\n\t\t\"apps\"\n\t\t{\n\t\t\t\"440\"\t\t\"1024\"\n\t\t}\n\t}\n\t\"1\"\n\t{\n\t\t\"path\"\t\t\"D:\\\\SteamLibrary\"\n\t\t\"apps\"\n\t\t{\n\t\t}\n\t}\n}\n";
+        let parsed = parse_text(text);
+        assert_eq!(get_path(&parsed, &["0", "path"]), Some(&serde_json::json!("C:\\Program Files (x86)\\Steam")));
+        assert_eq!(get_path(&parsed, &["0", "apps", "440"]), Some(&serde_json::json!("1024")));
+        assert_eq!(get_path(&parsed, &["1", "path"]), Some(&serde_json::json!("D:\\SteamLibrary")));
+        assert_eq!(get_path(&parsed, &["1", "apps"]), Some(&serde_json::Value::Null));
+    }
+
+    #[test]
+    fn parse_keeps_unknown_escapes() {
+        let parsed = parse_text("\"AppState\"\n{\n\t\"name\"\t\t\"a\\nb \\\"c\\\"\"\n\t\"appid\"\t\t\"440\"\n}\n");
+        assert_eq!(get_path(&parsed, &["name"]), Some(&serde_json::json!("a\\nb \"c\"")));
+        assert_eq!(get_path(&parsed, &["appid"]), Some(&serde_json::json!("440")));
+    }
+}
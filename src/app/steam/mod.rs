@@ -1,20 +1,47 @@
 #![allow(unused)]
+macro_rules! steam_command {
+    ($exec:expr, $args:expr) => {
+        std::process::Command::new($exec).args($args)
+    };
+}
+
 mod data;
 use data::*;
+mod steamid;
+use steamid::SteamID;
 mod error;
 use error::LoginError;
 mod manifest;
 use manifest::prelude::*;
-
-use std::{collections::{HashMap, HashSet}, path::PathBuf};
+mod launch;
+pub use launch::join_watches;
+use launch::{LaunchConfig, LaunchHandle, LaunchRequest, StartMode};
+mod process;
+pub use process::normalize_path;
+use process::ProcessMonitor;
+mod action;
+use action::ClientAction;
+mod appinfo;
+mod backup;
+mod hooks;
+mod journal;
+use journal::{Change, JournalEntry, RegistryValue};
+pub use backup::backups_root;
+
+use std::{collections::{HashMap, HashSet}, path::PathBuf, time::SystemTime};
 use registry::{Data, Hive, Security};
 use regex::Regex;
-use sysinfo::System;
 
 #[allow(unused)]
 pub mod prelude {
     pub use super::error::LoginError;
-    pub use super::data::{SteamID, SteamAccount, AppID, Thumbnail};
+    pub use super::data::{SteamAccount, AppStats, AppID, AppInfo, Thumbnail, UserdataAction, InstallState, InstallStatus, LaunchProfile};
+    pub use super::steamid::{SteamID, SteamIDError};
+    pub use super::action::ClientAction;
+    pub use super::launch::{LaunchConfig, LaunchEvent, LaunchHandle, LaunchRequest, LaunchStage, StartMode};
+    pub use super::hooks::{HookKind, Hooks};
+    pub use super::journal::{Change, JournalEntry};
+    pub use super::process::ProcessMonitor;
     pub use super::SteamModel;
 }
 
@@ -22,12 +49,6 @@ static STEAM_ROOT: &str = r"Software\Valve\Steam";
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-macro_rules! steam_command {
-    ($exec:expr, $args:expr) => {
-        std::process::Command::new($exec).args($args)
-    };
-}
-
 /// Steam Model
 /// 
 /// The Steam Model is a struct that contains all the data and functions required to interact with the Steam client.
@@ -39,6 +60,9 @@ macro_rules! steam_command {
 /// - `user_cache` - A vector of all detected users
 /// - `directories` - A hashmap of all detected directories and their associated games
 /// - `games` - A json object of all detected games and their manifests
+/// - `app_info` - A hashmap of metadata from appinfo.vdf for all installed and owned apps
+/// - `dry_run` - Only log what logins and launches would change instead of touching the registry, files or processes
+/// - `manifest_times` - When the manifest of each game was last read, to only read changed manifests again
 #[allow(unused)]
 #[derive(Debug, Default, Clone)]
 pub struct SteamModel {
@@ -47,6 +71,9 @@ pub struct SteamModel {
     pub user_cache: Vec<SteamAccount>,
     pub directories: HashMap<PathBuf, HashSet<i32>>,
     pub games: HashMap<AppID, serde_json::Value>, // GameID: Manifest
+    pub app_info: HashMap<i32, AppInfo>,
+    pub dry_run: bool,
+    pub manifest_times: HashMap<i32, SystemTime>,
 }
 
 impl SteamModel {
@@ -119,18 +146,18 @@ impl SteamModel {
             }
 
             // Get account SteamID
-            let id = match key.parse::<i64>() {
+            let steamid = match key.parse::<SteamID>() {
                 Ok(id) => id,
-                Err(_) => {
-                    eprintln!("Failed to load account '{}', Could not read 'SteamID'", key);
+                Err(e) => {
+                    eprintln!("Failed to load account '{}', Could not read 'SteamID': {}", key, e);
                     continue;
                 },
             };
-            let steamid: SteamID = SteamID::from(id);
 
             // Get account games
             let mut user_games: HashSet<i32> = HashSet::new();
-            let user_path = self.install_path.join("userdata").join(format!("{}", steamid.id3));
+            let mut user_stats: HashMap<i32, AppStats> = HashMap::new();
+            let user_path = self.install_path.join("userdata").join(format!("{}", steamid.account_id));
             let localconfig_path = user_path.join("config").join("localconfig.vdf");
 
             if !localconfig_path.exists() {
@@ -170,10 +197,11 @@ impl SteamModel {
                 continue;
             };
 
-            for (appid, _) in app_data.get("apps").unwrap().as_object().unwrap() {
+            for (appid, app) in app_data.get("apps").unwrap().as_object().unwrap() {
                 match appid.parse::<i32>() {
                     Ok(appid) => {
                         user_games.insert(appid);
+                        user_stats.insert(appid, AppStats::from(app));
                     },
                     Err(_) => {
                         eprintln!("Failed to parse appid: {}", appid);
@@ -181,10 +209,24 @@ impl SteamModel {
                 }
             }
 
+            // Get account details
+            let read = |key: &str| value.get(key).and_then(|x| x.as_str());
+            let flag = |key: &str, default: bool| read(key).map(|x| x == "1").unwrap_or(default);
+            let avatar = config_path.join("avatarcache").join(format!("{}.png", steamid.id64()));
+
             detected_accounts.push(SteamAccount {
                 name: name.to_string(),
-                id: Some(steamid),
                 games: user_games,
+                stats: user_stats,
+                persona_name: read("PersonaName").unwrap_or_default().to_string(),
+                timestamp: read("Timestamp").and_then(|x| x.parse::<u64>().ok()).filter(|x| *x > 0),
+                most_recent: flag("MostRecent", false),
+                remember_password: flag("RememberPassword", false),
+                // Older clients don't write this key, they only check RememberPassword
+                allow_auto_login: flag("AllowAutoLogin", true),
+                wants_offline_mode: flag("WantsOfflineMode", false),
+                avatar: avatar.exists().then_some(avatar),
+                id: Some(steamid),
             });
         }
 
@@ -276,6 +318,30 @@ impl SteamModel {
         Ok(detected_installs)
     }
 
+    /// Read metadata of all installed and owned apps from appinfo.vdf
+    /// 
+    /// Returns a hashmap of [`AppInfo`]s
+    /// 
+    /// # Warning
+    /// 
+    /// This function requires [`SteamModel::detect_accounts`] and [`SteamModel::detect_installs`] to be called first
+    pub fn detect_appinfo(&mut self) -> Result<&HashMap<i32, AppInfo>> {
+        let appinfo_path = self.install_path.join("appcache").join("appinfo.vdf");
+
+        if !appinfo_path.exists() {
+            eprintln!("Path does not exist: {:?}", appinfo_path);
+            return Err(Box::new(std::io::Error::new(std::io::ErrorKind::NotFound, "Appinfo path does not exist")));
+        }
+
+        let mut wanted: HashSet<i32> = self.games.keys().map(|x| x.id).collect();
+        for account in &self.user_cache {
+            wanted.extend(&account.games);
+        }
+
+        self.app_info = appinfo::parse_appinfo(appinfo_path, |appid| wanted.contains(&appid))?;
+        Ok(&self.app_info)
+    }
+
     /// Get all installed apps
     /// 
     /// Returns a vector of [`AppID`]s
@@ -291,6 +357,60 @@ impl SteamModel {
         self.games.get(appid)
     }
 
+    /// Get the install state of a game from its manifest
+    pub fn install_state(&self, appid: &AppID) -> InstallState {
+        self.games.get(appid).map(InstallState::from).unwrap_or_default()
+    }
+
+    /// Get the size of a game on disk in bytes, from `SizeOnDisk` in its manifest
+    pub fn size_on_disk(&self, appid: &AppID) -> u64 {
+        self.games
+            .get(appid)
+            .and_then(|x| x.get("SizeOnDisk"))
+            .and_then(|x| x.as_str())
+            .and_then(|x| x.parse::<u64>().ok())
+            .unwrap_or_default()
+    }
+
+    /// Get the library folder a game is installed in
+    pub fn library_folder(&self, appid: &i32) -> Option<&PathBuf> {
+        self.directories
+            .iter()
+            .find(|(_, apps)| apps.contains(appid))
+            .map(|(path, _)| path)
+    }
+
+    /// Read the manifests of all installed games again to pick up update and download progress
+    /// 
+    /// Only manifests modified since they were last read are parsed.
+    /// Returns `true` if any manifest was read again
+    pub fn reload_manifests(&mut self) -> bool {
+        let mut changed = false;
+        for (app, manifest) in self.games.iter_mut() {
+            // Games are installed to `<library>/steamapps/common/<installdir>`
+            let Some(steamapps_path) = app.location.parent().and_then(|x| x.parent()) else {
+                continue;
+            };
+            let manifest_path = steamapps_path.join(format!("appmanifest_{}.acf", app.id));
+            let Ok(modified) = manifest_path.metadata().and_then(|x| x.modified()) else {
+                continue;
+            };
+            if self.manifest_times.get(&app.id) == Some(&modified) {
+                continue;
+            }
+
+            match manifest::parse_manifest(manifest_path) {
+                Ok(data) => {
+                    *manifest = data;
+                    self.manifest_times.insert(app.id, modified);
+                    changed = true;
+                },
+                Err(e) => log::debug!("Failed to reload manifest of {}: {}", app.name, e),
+            }
+        }
+        changed
+    }
+
     /// Get the thumbnail for a game
     /// 
     /// Returns a [`Thumbnail`] struct containing the portrait and landscape paths
@@ -322,98 +442,425 @@ impl SteamModel {
 
     /// Set the login account in registry
     /// 
-    /// Sets the AutoLoginUser and RememberPassword values in the registry
-    pub fn set_login_account(&self, account: &String) -> Result<()> {
-        let regkey = Hive::CurrentUser.open(STEAM_ROOT, Security::AllAccess)?;
-
-        if regkey.value("AutoLoginUser")?.to_string() == *account {
+    /// Sets the AutoLoginUser and RememberPassword values in the registry.
+    /// In dry-run mode the changes are only logged
+    pub fn set_login_account(&self, account: &String, mode: StartMode) -> Result<()> {
+        if !self.needs_switch(account, mode) {
             return Err(Box::new(LoginError::AlreadyLoggedIn));
         }
+        if self.dry_run {
+            // Nothing is written, callers report the changes from login_changes
+            return Ok(());
+        }
+
+        self.update_loginusers(account, mode)?;
 
         // Set AutoLoginUser and RememberPassword
-        let user_data: Data = Data::String(utfx::WideCString::from_str(&account).unwrap().into());
-        regkey.set_value("AutoLoginUser", &user_data)?;
-        regkey.set_value("RememberPassword", &Data::U32(1))?;
+        let description = format!("Switch to '{}'", account);
+        self.write_registry("AutoLoginUser", Some(RegistryValue::String(account.clone())), &description)?;
+        self.write_registry("RememberPassword", Some(RegistryValue::U32(1)), &description)?;
 
         Ok(())
     }
 
-    /// Run steam with optional arguments
-    pub fn restart(&self, args: Option<Vec<String>>, exit_after: bool) -> Result<()> {
-        let steam_exe = self.install_path.join("steam.exe");
+    /// Read a value of the Steam registry key, `None` if it doesn't exist or has a type wait doesn't write
+    fn read_registry(&self, name: &str) -> Option<RegistryValue> {
+        let regkey = Hive::CurrentUser.open(STEAM_ROOT, Security::Read).ok()?;
+        match regkey.value(name).ok()? {
+            Data::String(value) => Some(RegistryValue::String(value.to_string_lossy())),
+            Data::U32(value) => Some(RegistryValue::U32(value)),
+            _ => None,
+        }
+    }
 
-        // Spawn thread to start steam
-        std::thread::spawn(move || {
-            let mut system = System::new_all();
+    /// Set or delete a value of the Steam registry key, recording its previous value in the change journal first
+    fn write_registry(&self, name: &str, value: Option<RegistryValue>, description: &str) -> Result<()> {
+        journal::record_registry(name, self.read_registry(name), description)?;
 
-            // Close steam if running
-            if system.processes_by_exact_name("steam.exe".as_ref()).count() > 0 {
-                eprintln!("Steam is running, closing...");
-                steam_command!(&steam_exe, ["-exitsteam"]).output().expect("Failed to close Steam");
+        let regkey = Hive::CurrentUser.open(STEAM_ROOT, Security::AllAccess)?;
+        match value {
+            Some(RegistryValue::String(value)) => regkey.set_value(name, &Data::String(utfx::WideCString::from_str(&value)?))?,
+            Some(RegistryValue::U32(value)) => regkey.set_value(name, &Data::U32(value))?,
+            None => regkey.delete_value(name)?,
+        }
 
-                // Wait for steam to close
-                while system.processes_by_exact_name("steam.exe".as_ref()).count() > 0 {
-                    eprintln!("Waiting for Steam to close...");
-                    std::thread::sleep(std::time::Duration::from_secs(1));
-                    system = System::new_all(); // system.refresh_all() just doesn't work ig ¯\_(ツ)_/¯
+        Ok(())
+    }
+
+    /// Get every recorded change, newest first
+    pub fn journal(&self) -> Vec<JournalEntry> {
+        let mut entries = journal::load();
+        entries.reverse();
+        entries
+    }
+
+    /// Undo a recorded change by putting back the previous registry value or file
+    /// 
+    /// The current state is recorded first, so restoring can be undone as well.
+    /// Steam has to be closed, it overwrites its config on exit
+    pub fn restore_change(&mut self, entry: &JournalEntry) -> Result<()> {
+        if self.dry_run {
+            return Err(Box::new(LoginError::Other("Changes can't be restored in dry-run mode".to_string())));
+        }
+        if self.is_steam_running() {
+            return Err(Box::new(LoginError::Other("Steam has to be closed to restore a change".to_string())));
+        }
+
+        let description = format!("Restore \"{}\"", entry.description);
+        match &entry.change {
+            Change::Registry { name, previous } => self.write_registry(name, previous.clone(), &description)?,
+            Change::File { path, backup: Some(backup) } => {
+                let text = std::fs::read_to_string(backup)?;
+                manifest::write_manifest(path, &text, &description)?;
+            },
+            Change::File { path, backup: None } => {
+                journal::record_file(path, &description)?;
+                if path.exists() {
+                    std::fs::remove_file(path)?;
                 }
-                eprintln!("Steam closed");
-            }
+            },
+        }
+        journal::mark_restored(entry.id)?;
+        log::info!("Restored {}", entry.target());
 
-            // Start steam
-            eprintln!("Starting Steam with args: {:?}", args.clone().unwrap_or(vec![]));
-            steam_command!(&steam_exe, args.unwrap_or(vec![])).spawn().expect("Failed to start Steam");
+        // Pick up restored loginusers.vdf and localconfig.vdf changes
+        if let Err(e) = self.detect_accounts() {
+            log::warn!("Failed to detect accounts after restoring: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Remove recorded changes and backups older than `max_age_days`
+    pub fn prune_backups(&self, max_age_days: u64) -> Result<usize> {
+        journal::prune(max_age_days)
+    }
+
+    /// Describe what [`SteamModel::set_login_account`] changes
+    pub fn login_changes(&self, account: &str, mode: StartMode) -> Vec<String> {
+        let offline = if mode == StartMode::Offline { 1 } else { 0 };
+        vec![
+            format!(
+                "Set MostRecent of '{}' to 1 and of every other account to 0 in {}",
+                account,
+                self.install_path.join("config").join("loginusers.vdf").display(),
+            ),
+            format!("Set AllowAutoLogin to 1, WantsOfflineMode and SkipOfflineModeWarning to {} for '{}'", offline, account),
+            format!("Set HKCU\\{}\\AutoLoginUser to '{}'", STEAM_ROOT, account),
+            format!("Set HKCU\\{}\\RememberPassword to 1", STEAM_ROOT),
+        ]
+    }
+
+    /// Mark the account as the most recent one in `loginusers.vdf` and apply its start mode
+    /// 
+    /// Newer clients ignore `AutoLoginUser` if `MostRecent` points at another account.
+    /// The file is only written while Steam is closed, as Steam overwrites it on exit.
+    /// The previous file is recorded in the change journal
+    fn update_loginusers(&self, account: &str, mode: StartMode) -> Result<()> {
+        if self.is_steam_running() {
+            return Err(Box::new(LoginError::Other("Steam has to be closed to switch accounts".to_string())));
+        }
 
-            // Exit the application
-            if exit_after { std::process::exit(0); }
-        });
+        let loginusers_path = self.install_path.join("config").join("loginusers.vdf");
+        let loginusers_data = parse_manifest(loginusers_path.clone())?;
+        let mut text = std::fs::read_to_string(&loginusers_path)?;
+
+        let users = loginusers_data.as_object().ok_or(ManifestParseError)?;
+        let Some(target) = users.iter().find(|(_, x)| x.get("AccountName").and_then(|x| x.as_str()) == Some(account)).map(|(k, _)| k.clone()) else {
+            return Err(Box::new(LoginError::Other(format!("Account '{}' is not in loginusers.vdf", account))));
+        };
+
+        for id in users.keys() {
+            let most_recent = if *id == target { "1" } else { "0" };
+            text = manifest::set_value(&text, &["users", id, "MostRecent"], most_recent).ok_or(ManifestParseError)?;
+        }
+
+        let offline = if mode == StartMode::Offline { "1" } else { "0" };
+        for (key, value) in [("AllowAutoLogin", "1"), ("WantsOfflineMode", offline), ("SkipOfflineModeWarning", offline)] {
+            text = manifest::set_value(&text, &["users", &target, key], value).ok_or(ManifestParseError)?;
+        }
+
+        manifest::write_manifest(&loginusers_path, &text, &format!("Switch to '{}'", account))?;
+        log::info!("Updated loginusers.vdf for '{}' ({})", account, mode);
 
         Ok(())
     }
+
+    /// Set the launch options of a game in an account's `localconfig.vdf`
+    /// 
+    /// Only the `LaunchOptions` value is changed, the rest of the file is kept as is and the old file is recorded in the change journal.
+    /// Steam has to be closed, it rewrites `localconfig.vdf` on exit.
+    pub fn set_launch_options(&mut self, account: &str, appid: &i32, options: &str) -> Result<()> {
+        if self.dry_run {
+            return Err(Box::new(LoginError::Other("Launch options can't be changed in dry-run mode".to_string())));
+        }
+        if self.is_steam_running() {
+            return Err(Box::new(LoginError::Other("Steam has to be closed to change launch options".to_string())));
+        }
+        let Some(steam_account) = self.user_cache.iter_mut().find(|x| x.name == account) else {
+            return Err(Box::new(LoginError::Other(format!("Unknown account '{}'", account))));
+        };
+        let Some(id) = steam_account.id else {
+            return Err(Box::new(LoginError::Other(format!("Account '{}' has no SteamID", account))));
+        };
+
+        let localconfig_path = self.install_path.join("userdata").join(id.account_id.to_string()).join("config").join("localconfig.vdf");
+        let text = std::fs::read_to_string(&localconfig_path)?;
+        let path = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps", &appid.to_string(), "LaunchOptions"];
+        let Some(text) = manifest::set_value(&text, &path, &manifest::escape(options)) else {
+            return Err(Box::new(LoginError::Other(format!("{} is not in the localconfig.vdf of '{}'", appid, account))));
+        };
+
+        manifest::write_manifest(&localconfig_path, &text, &format!("Set launch options of {} for '{}'", appid, account))?;
+        log::info!("Set launch options of {} for '{}' to '{}'", appid, account, options);
+
+        let stats = steam_account.stats.entry(*appid).or_default();
+        stats.launch_options = Some(options.to_string()).filter(|x| !x.is_empty());
+
+        Ok(())
+    }
+
+    /// Remove an account from the Steam login list
+    /// 
+    /// `loginusers.vdf` and the account's `userdata` are copied to a new backup folder first,
+    /// only the `config` folder if `userdata` is kept and the whole folder if it is deleted.
+    /// Then the account is removed from `loginusers.vdf` and its `userdata` folder kept, deleted or moved into the backup.
+    /// Steam has to be closed, it rewrites `loginusers.vdf` on exit.
+    /// Returns the backup folder
+    pub fn forget_account(&mut self, account: &str, userdata: UserdataAction) -> Result<PathBuf> {
+        if self.dry_run {
+            return Err(Box::new(LoginError::Other("Accounts can't be forgotten in dry-run mode".to_string())));
+        }
+        if self.is_steam_running() {
+            return Err(Box::new(LoginError::Other("Steam has to be closed to forget an account".to_string())));
+        }
+        let Some(steam_account) = self.user_cache.iter().find(|x| x.name == account).cloned() else {
+            return Err(Box::new(LoginError::Other(format!("Unknown account '{}'", account))));
+        };
+        let Some(id) = steam_account.id else {
+            return Err(Box::new(LoginError::Other(format!("Account '{}' has no SteamID", account))));
+        };
+
+        let loginusers_path = self.install_path.join("config").join("loginusers.vdf");
+        let userdata_path = self.install_path.join("userdata").join(id.account_id.to_string());
+
+        let backup = backup::create_backup(&format!("forget-{}", account))?;
+        let userdata_backup = backup.join("userdata").join(id.account_id.to_string());
+        std::fs::copy(&loginusers_path, backup.join("loginusers.vdf"))?;
+        match userdata {
+            UserdataAction::Keep if userdata_path.join("config").exists() => {
+                backup::copy_dir(&userdata_path.join("config"), &userdata_backup.join("config"))?;
+            },
+            // Saves, screenshots and remote storage are gone otherwise
+            UserdataAction::Delete if userdata_path.exists() => backup::copy_dir(&userdata_path, &userdata_backup)?,
+            _ => {},
+        }
+
+        let text = std::fs::read_to_string(&loginusers_path)?;
+        match manifest::remove_key(&text, &["users", &id.id64().to_string()]) {
+            Some(text) => manifest::write_manifest(&loginusers_path, &text, &format!("Forget '{}'", account))?,
+            None => log::warn!("'{}' was not in loginusers.vdf", account),
+        }
+
+        if userdata_path.exists() {
+            match userdata {
+                UserdataAction::Keep => {},
+                UserdataAction::Archive => backup::move_dir(&userdata_path, &userdata_backup)?,
+                UserdataAction::Delete => std::fs::remove_dir_all(&userdata_path)?,
+            }
+        }
+
+        // Don't let Steam try to log into an account it no longer knows
+        if self.is_login_account(account) {
+            self.write_registry("AutoLoginUser", Some(RegistryValue::String(String::new())), &format!("Forget '{}'", account))?;
+        }
+
+        self.user_cache.retain(|x| x.name != account);
+        log::info!("Forgot account '{}', backup in {:?}", account, backup);
+
+        Ok(backup)
+    }
+
+    /// Check whether Steam has to be restarted to log into the account with the start mode
+    /// 
+    /// Invisible accounts log in online and go invisible afterwards, so only offline mode needs a restart
+    pub fn needs_switch(&self, account: &str, mode: StartMode) -> bool {
+        if !self.is_login_account(account) {
+            return true;
+        }
+
+        let loginusers_path = self.install_path.join("config").join("loginusers.vdf");
+        let (Ok(data), Ok(text)) = (parse_manifest(loginusers_path.clone()), std::fs::read_to_string(&loginusers_path)) else {
+            return false;
+        };
+        let Some(id) = data.as_object().and_then(|users| {
+            users.iter().find(|(_, x)| x.get("AccountName").and_then(|x| x.as_str()) == Some(account)).map(|(k, _)| k.clone())
+        }) else {
+            return false;
+        };
+
+        let offline = manifest::get_value(&text, &["users", &id, "WantsOfflineMode"]) == Some("1");
+        offline != (mode == StartMode::Offline)
+    }
+
+    /// Check whether a Steam client is running
+    pub fn is_steam_running(&self) -> bool {
+        sysinfo::System::new_all().processes_by_exact_name("steam.exe".as_ref()).count() > 0
+    }
+
+    /// Check whether a launch pipeline for the account closes a running Steam
+    /// 
+    /// Steam is restarted to switch accounts or start modes, and to pass environment variables on to a game
+    pub fn restarts_steam(&self, account: &str, mode: StartMode, env: &[(String, String)]) -> bool {
+        self.needs_switch(account, mode) || !env.is_empty()
+    }
+
+    /// Check whether the provided account is the one Steam will log into
+    pub fn is_login_account(&self, account: &str) -> bool {
+        let regkey = match Hive::CurrentUser.open(STEAM_ROOT, Security::Read) {
+            Ok(regkey) => regkey,
+            Err(_) => return false,
+        };
+        regkey.value("AutoLoginUser").is_ok_and(|user| user.to_string() == account)
+    }
+
+    /// Get the account id and pid of the running Steam client
+    /// 
+    /// Reads `ActiveProcess` from the registry, falling back to `registry.vdf`.
+    /// Returns `None` while no client is running, the account id is `0` until the client is logged in
+    pub fn active_process(&self) -> Option<(u32, u32)> {
+        if let Ok(regkey) = Hive::CurrentUser.open(format!(r"{}\ActiveProcess", STEAM_ROOT), Security::Read) {
+            let active_user = match regkey.value("ActiveUser") {
+                Ok(Data::U32(active_user)) => Some(active_user),
+                _ => None,
+            };
+            let pid = match regkey.value("pid") {
+                Ok(Data::U32(pid)) => Some(pid),
+                _ => None,
+            };
+            if let (Some(active_user), Some(pid)) = (active_user, pid) {
+                return Some((active_user, pid));
+            }
+        }
+
+        // Clients without a registry keep the same values in registry.vdf
+        let registry_path = self.install_path.parent()?.join("registry.vdf");
+        let registry_data = manifest::parse_manifest(registry_path).ok()?;
+        let active_process = manifest::get_path(&registry_data, &["HKCU", "Software", "Valve", "Steam", "ActiveProcess"])?;
+        let active_user = active_process.get("ActiveUser")?.as_str()?.parse::<u32>().ok()?;
+        let pid = active_process.get("pid")?.as_str()?.parse::<u32>().ok()?;
+
+        Some((active_user, pid))
+    }
+
+    /// Get the id of the game the client reports as running
+    /// 
+    /// Reads `RunningAppID` from the registry, falling back to `registry.vdf`
+    pub fn running_app_id(&self) -> Option<i32> {
+        let appid = match Hive::CurrentUser.open(STEAM_ROOT, Security::Read).map(|regkey| regkey.value("RunningAppID")) {
+            Ok(Ok(Data::U32(appid))) => appid as i32,
+            _ => {
+                let registry_path = self.install_path.parent()?.join("registry.vdf");
+                let registry_data = manifest::parse_manifest(registry_path).ok()?;
+                manifest::get_path(&registry_data, &["HKCU", "Software", "Valve", "Steam", "RunningAppID"])?
+                    .as_str()?
+                    .parse::<i32>()
+                    .ok()?
+            },
+        };
+
+        if appid == 0 { None } else { Some(appid) }
+    }
+
+    /// Hand a game launch to the running client without switching accounts
+    /// 
+    /// Steam brings an already running game to the front instead of starting it again.
+    /// In dry-run mode nothing is started, the step that would be taken is returned instead
+    pub fn focus_game(&self, appid: &i32) -> Result<Option<String>> {
+        let steam = self.install_path.join("steam.exe");
+        let url = format!("steam://rungameid/{}", appid);
+        if self.dry_run {
+            return Ok(Some(format!("Run {} {}", steam.display(), url)));
+        }
+        steam_command!(steam, [url]).spawn()?;
+        Ok(None)
+    }
+
+    /// Run the launch pipeline for a [`LaunchRequest`]
+    /// 
+    /// Steam is closed, the account is switched and Steam is started again with `args` on a separate thread.
+    /// Once the client is logged in the optional `url` is dispatched to it.
+    /// The pipeline finishes once the game is started, use [`SteamModel::watch_exit`] to switch back to `restore` afterwards.
+    /// Progress is reported through the returned [`LaunchHandle`].
+    /// In dry-run mode nothing is run, every step is reported as a [`LaunchEvent::DryRun`] instead
+    pub fn restart(&self, request: LaunchRequest, config: &LaunchConfig) -> LaunchHandle {
+        launch::spawn(self.clone(), request, config.clone())
+    }
     
+    /// Wait for a launched game to exit on a separate thread, then run its post-exit hooks and switch back to the `restore` account of the request
+    /// 
+    /// Switching back waits until no other game is running
+    pub fn watch_exit(&self, request: LaunchRequest, config: &LaunchConfig) -> LaunchHandle {
+        launch::spawn_watch(self.clone(), request, config.clone())
+    }
+
     /// Initiate a login with the provided account
     /// 
     /// this function will set the login account and start steam
-    pub fn login(&self, account: &String, exit_after: bool) -> Result<()> {
-        match self.set_login_account(account) {
-            Ok(_) => (),
-            Err(e) => {
-                return Err(e);
-            },
+    pub fn login(&self, account: &String, config: &LaunchConfig) -> Result<LaunchHandle> {
+        if !self.needs_switch(account, config.start_mode(account)) {
+            return Err(Box::new(LoginError::AlreadyLoggedIn));
         }
 
-        eprintln!("Successfully set login account: {}", account);
+        Ok(self.restart(LaunchRequest { account: account.clone(), ..Default::default() }, config))
+    }
 
-        self.restart(None, exit_after)?;
+    /// Dispatch a `steam://` url to Steam once it is logged into the account
+    pub fn dispatch(&self, account: &str, url: String, config: &LaunchConfig) -> LaunchHandle {
+        self.restart(LaunchRequest { account: account.to_string(), url: Some(url), ..Default::default() }, config)
+    }
 
-        Ok(())
+    /// Install a game with the provided account
+    pub fn install_game(&self, account: &str, appid: &i32, config: &LaunchConfig) -> LaunchHandle {
+        self.dispatch(account, format!("steam://install/{}", appid), config)
+    }
+
+    /// Run a client action for a game with the provided account
+    pub fn client_action(&self, account: &str, appid: &i32, action: ClientAction, config: &LaunchConfig) -> LaunchHandle {
+        self.dispatch(account, action.url(*appid), config)
     }
 
     /// Launch a game with the provided account and appid
     /// 
-    /// this function will login to the account and start the game
-    pub fn launch_game(&self, account: &String, appid: &i32, close_after: bool) -> Result<()> {
-        let args = vec![
-            "-applaunch".to_string(),
-            appid.to_string(),
-        ];
-
-        match self.set_login_account(account) {
-            Ok(_) => (),
-            Err(e) => {
-                if e.downcast_ref::<LoginError>().is_some_and(|e| e == &LoginError::AlreadyLoggedIn) {
-                    std::process::Command::new(self.install_path.join("steam.exe"))
-                        .args(args)
-                        .spawn().unwrap();
-                    if close_after { std::process::exit(0); }
-                    return Ok(());
-                }
-                return Err(e);
-            },
-        }
+    /// this function will login to the account and start the game with the arguments and environment of `profile`,
+    /// switching back to the `restore` account once the game exits
+    pub fn launch_game(&self, account: &String, appid: &i32, restore: Option<String>, profile: Option<&LaunchProfile>, config: &LaunchConfig) -> Result<LaunchHandle> {
+        Ok(self.restart(LaunchRequest {
+            account: account.clone(),
+            url: Some(format!("steam://rungameid/{}", appid)),
+            appid: Some(*appid),
+            game_args: profile.map(|x| x.args()).unwrap_or_default(),
+            env: profile.map(|x| x.env()).unwrap_or_default(),
+            restore: restore.filter(|x| x != account),
+            ..Default::default()
+        }, config))
+    }
+}
 
-        self.restart(Some(args), close_after)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(())
+    #[test]
+    fn focus_game_dry_run() {
+        // Spawning the missing steam.exe would fail, so getting a step back means nothing was started
+        let model = SteamModel {
+            install_path: PathBuf::from("/nonexistent/Steam"),
+            dry_run: true,
+            ..Default::default()
+        };
+        let step = model.focus_game(&440).unwrap();
+        let steam = PathBuf::from("/nonexistent/Steam").join("steam.exe");
+        assert_eq!(step, Some(format!("Run {} steam://rungameid/440", steam.display())));
     }
-}
\ No newline at end of file
+}
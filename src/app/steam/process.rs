@@ -0,0 +1,101 @@
+use std::{collections::HashSet, path::Path, sync::{Arc, Mutex}, time::Duration};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use super::{data::AppID, SteamModel};
+
+/// Process Monitor
+/// 
+/// Keeps track of running games on a background thread.
+/// A game counts as running if a process is running from its install location or the client reports it as `RunningAppID`
+#[derive(Debug, Default, Clone)]
+pub struct ProcessMonitor {
+    running: Arc<Mutex<HashSet<i32>>>,
+}
+
+impl ProcessMonitor {
+    /// Start monitoring all installed games of the model
+    /// 
+    /// The first check is done before returning, so [`running`](Self::running) is accurate right away.
+    /// The monitor thread exits once every clone of the monitor has been dropped
+    pub fn start(model: &SteamModel) -> Self {
+        let mut system = System::new();
+        let monitor = Self {
+            running: Arc::new(Mutex::new(detect(&mut system, model))),
+        };
+        let running = Arc::downgrade(&monitor.running);
+        let model = model.clone();
+
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(Duration::from_secs(2));
+                let Some(running) = running.upgrade() else {
+                    break;
+                };
+                *running.lock().unwrap() = detect(&mut system, &model);
+            }
+        });
+
+        monitor
+    }
+
+    /// Get the ids of all currently running games
+    pub fn running(&self) -> HashSet<i32> {
+        self.running.lock().unwrap().clone()
+    }
+}
+
+/// Get the ids of all running games of the model
+fn detect(system: &mut System, model: &SteamModel) -> HashSet<i32> {
+    refresh(system);
+    let mut detected = running_games(system, model.games.keys());
+    if let Some(appid) = model.running_app_id() {
+        detected.insert(appid);
+    }
+    detected
+}
+
+/// Refresh all processes along with their executable paths
+pub fn refresh(system: &mut System) {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+}
+
+/// Get the ids of all games with a process running from their install location
+/// 
+/// Requires the processes in `system` to be refreshed with [`refresh`]
+pub fn running_games<'a>(system: &System, games: impl IntoIterator<Item = &'a AppID>) -> HashSet<i32> {
+    let locations: Vec<(i32, String)> = games
+        .into_iter()
+        .filter(|app| !app.location.as_os_str().is_empty())
+        .map(|app| (app.id, normalize_path(&app.location)))
+        .collect();
+
+    let mut running = HashSet::new();
+    for process in system.processes().values() {
+        let Some(exe) = process.exe() else {
+            continue;
+        };
+        let exe = normalize_path(exe);
+        for (id, location) in &locations {
+            if exe.starts_with(location) {
+                running.insert(*id);
+            }
+        }
+    }
+
+    running
+}
+
+/// Lowercase a path with backslash separators and a trailing separator
+/// 
+/// Windows paths are case-insensitive and may use either separator
+pub fn normalize_path(path: &Path) -> String {
+    let mut path = path.to_string_lossy().to_lowercase().replace('/', "\\");
+    if !path.ends_with('\\') {
+        path.push('\\');
+    }
+    path
+}
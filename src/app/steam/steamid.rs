@@ -0,0 +1,571 @@
+use core::fmt;
+use std::str::FromStr;
+
+const ACCOUNT_ID_MASK: u64 = 0xFFFFFFFF;
+const INSTANCE_MASK: u64 = 0x000FFFFF;
+const INSTANCE_SHIFT: u64 = 32;
+const TYPE_SHIFT: u64 = 52;
+const UNIVERSE_SHIFT: u64 = 56;
+
+/// Instance of a desktop client, the default for individual accounts
+pub const DESKTOP_INSTANCE: u32 = 1;
+/// Instance flag of a group chat
+const CLAN_CHAT_FLAG: u32 = 0x80000;
+/// Instance flag of a lobby
+const LOBBY_CHAT_FLAG: u32 = 0x40000;
+
+/// Steam Universe
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Universe {
+    Invalid,
+    #[default]
+    Public,
+    Beta,
+    Internal,
+    Dev,
+}
+
+impl TryFrom<u64> for Universe {
+    type Error = SteamIDError;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Universe::Invalid),
+            1 => Ok(Universe::Public),
+            2 => Ok(Universe::Beta),
+            3 => Ok(Universe::Internal),
+            4 => Ok(Universe::Dev),
+            _ => Err(SteamIDError::InvalidUniverse(value)),
+        }
+    }
+}
+
+impl From<Universe> for u64 {
+    fn from(value: Universe) -> Self {
+        match value {
+            Universe::Invalid => 0,
+            Universe::Public => 1,
+            Universe::Beta => 2,
+            Universe::Internal => 3,
+            Universe::Dev => 4,
+        }
+    }
+}
+
+/// Steam Account Type
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountType {
+    Invalid,
+    #[default]
+    Individual,
+    Multiseat,
+    GameServer,
+    AnonGameServer,
+    Pending,
+    ContentServer,
+    Clan,
+    Chat,
+    ConsoleUser,
+    AnonUser,
+}
+
+impl TryFrom<u64> for AccountType {
+    type Error = SteamIDError;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AccountType::Invalid),
+            1 => Ok(AccountType::Individual),
+            2 => Ok(AccountType::Multiseat),
+            3 => Ok(AccountType::GameServer),
+            4 => Ok(AccountType::AnonGameServer),
+            5 => Ok(AccountType::Pending),
+            6 => Ok(AccountType::ContentServer),
+            7 => Ok(AccountType::Clan),
+            8 => Ok(AccountType::Chat),
+            9 => Ok(AccountType::ConsoleUser),
+            10 => Ok(AccountType::AnonUser),
+            _ => Err(SteamIDError::InvalidType(value)),
+        }
+    }
+}
+
+impl From<AccountType> for u64 {
+    fn from(value: AccountType) -> Self {
+        match value {
+            AccountType::Invalid => 0,
+            AccountType::Individual => 1,
+            AccountType::Multiseat => 2,
+            AccountType::GameServer => 3,
+            AccountType::AnonGameServer => 4,
+            AccountType::Pending => 5,
+            AccountType::ContentServer => 6,
+            AccountType::Clan => 7,
+            AccountType::Chat => 8,
+            AccountType::ConsoleUser => 9,
+            AccountType::AnonUser => 10,
+        }
+    }
+}
+
+impl AccountType {
+    /// The letter used for the type in the `[U:1:N]` format
+    fn letter(&self) -> char {
+        match self {
+            AccountType::Invalid => 'I',
+            AccountType::Individual => 'U',
+            AccountType::Multiseat => 'M',
+            AccountType::GameServer => 'G',
+            AccountType::AnonGameServer => 'A',
+            AccountType::Pending => 'P',
+            AccountType::ContentServer => 'C',
+            AccountType::Clan => 'g',
+            AccountType::Chat => 'T',
+            AccountType::ConsoleUser => 'i', // Not assigned by Valve, only used to round trip
+            AccountType::AnonUser => 'a',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SteamIDError {
+    InvalidFormat(String),
+    InvalidUniverse(u64),
+    InvalidType(u64),
+    InvalidInstance(u64),
+    /// Custom profile urls can only be resolved through the Web API
+    VanityUrl(String),
+}
+
+impl fmt::Display for SteamIDError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SteamIDError::InvalidFormat(input) => write!(f, "Not a SteamID: '{}'", input),
+            SteamIDError::InvalidUniverse(universe) => write!(f, "Invalid universe: {}", universe),
+            SteamIDError::InvalidType(kind) => write!(f, "Invalid account type: {}", kind),
+            SteamIDError::InvalidInstance(instance) => write!(f, "Invalid instance: {}", instance),
+            SteamIDError::VanityUrl(name) => write!(f, "Custom profile url '{}' can't be resolved offline", name),
+        }
+    }
+}
+
+impl std::error::Error for SteamIDError {}
+
+/// Steam ID
+///
+/// Stored as its 64-bit form
+///
+/// # Variables
+///
+/// - `universe` - The Steam universe, `Public` for every normal account
+/// - `account_type` - The kind of account
+/// - `instance` - The instance, `1` for desktop clients
+/// - `account_id` - The 32-bit account id, used for `userdata` folders
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "u64", into = "u64")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SteamID {
+    pub universe: Universe,
+    pub account_type: AccountType,
+    pub instance: u32,
+    pub account_id: u32,
+}
+
+impl TryFrom<u64> for SteamID {
+    type Error = SteamIDError;
+
+    fn try_from(id64: u64) -> Result<Self, Self::Error> {
+        let id = Self {
+            universe: Universe::try_from(id64 >> UNIVERSE_SHIFT)?,
+            account_type: AccountType::try_from((id64 >> TYPE_SHIFT) & 0xF)?,
+            instance: ((id64 >> INSTANCE_SHIFT) & INSTANCE_MASK) as u32,
+            account_id: (id64 & ACCOUNT_ID_MASK) as u32,
+        };
+        id.validate()?;
+        Ok(id)
+    }
+}
+
+impl From<SteamID> for u64 {
+    fn from(id: SteamID) -> Self {
+        id.id64()
+    }
+}
+
+impl SteamID {
+    /// Create the id of an individual account in the public universe
+    pub fn individual(account_id: u32) -> Self {
+        Self {
+            universe: Universe::Public,
+            account_type: AccountType::Individual,
+            instance: DESKTOP_INSTANCE,
+            account_id,
+        }
+    }
+
+    fn validate(&self) -> Result<(), SteamIDError> {
+        if self.universe == Universe::Invalid {
+            return Err(SteamIDError::InvalidUniverse(0));
+        }
+        if self.account_type == AccountType::Invalid {
+            return Err(SteamIDError::InvalidType(0));
+        }
+        if self.instance as u64 > INSTANCE_MASK {
+            return Err(SteamIDError::InvalidInstance(self.instance as u64));
+        }
+        // Individual accounts only ever run as all, desktop, console or web instances
+        if self.account_type == AccountType::Individual && self.instance > 4 {
+            return Err(SteamIDError::InvalidInstance(self.instance as u64));
+        }
+        if self.account_type == AccountType::Clan && self.instance != 0 {
+            return Err(SteamIDError::InvalidInstance(self.instance as u64));
+        }
+        Ok(())
+    }
+
+    /// The 64-bit form, e.g. `76561197960287930`
+    pub fn id64(&self) -> u64 {
+        (u64::from(self.universe) << UNIVERSE_SHIFT)
+            | (u64::from(self.account_type) << TYPE_SHIFT)
+            | ((self.instance as u64 & INSTANCE_MASK) << INSTANCE_SHIFT)
+            | self.account_id as u64
+    }
+
+    /// The legacy form, e.g. `STEAM_1:0:11101`
+    ///
+    /// Games from before the Orange Box print the public universe as `0`, this always uses the real universe
+    pub fn steam2(&self) -> String {
+        format!("STEAM_{}:{}:{}", u64::from(self.universe), self.account_id & 1, self.account_id >> 1)
+    }
+
+    /// The modern form, e.g. `[U:1:22202]`
+    pub fn steam3(&self) -> String {
+        let letter = match self.account_type {
+            AccountType::Chat if self.instance & CLAN_CHAT_FLAG != 0 => 'c',
+            AccountType::Chat if self.instance & LOBBY_CHAT_FLAG != 0 => 'L',
+            kind => kind.letter(),
+        };
+        let append_instance = match self.account_type {
+            AccountType::AnonGameServer | AccountType::Multiseat => true,
+            AccountType::Individual => self.instance != DESKTOP_INSTANCE,
+            _ => false,
+        };
+
+        if append_instance {
+            format!("[{}:{}:{}:{}]", letter, u64::from(self.universe), self.account_id, self.instance)
+        } else {
+            format!("[{}:{}:{}]", letter, u64::from(self.universe), self.account_id)
+        }
+    }
+
+    /// The community page of the account or group
+    pub fn profile_url(&self) -> String {
+        match self.account_type {
+            AccountType::Clan => format!("https://steamcommunity.com/gid/{}", self.id64()),
+            _ => format!("https://steamcommunity.com/profiles/{}", self.id64()),
+        }
+    }
+
+    fn parse_steam2(input: &str) -> Result<Self, SteamIDError> {
+        let invalid = || SteamIDError::InvalidFormat(input.to_string());
+        let parts: Vec<&str> = input.strip_prefix("STEAM_").ok_or_else(invalid)?.split(':').collect();
+        let [universe, y, z] = parts[..] else {
+            return Err(invalid());
+        };
+
+        let universe = universe.parse::<u64>().map_err(|_| invalid())?;
+        let y = y.parse::<u32>().map_err(|_| invalid())?;
+        let z = z.parse::<u32>().map_err(|_| invalid())?;
+        if y > 1 || z > u32::MAX >> 1 {
+            return Err(invalid());
+        }
+
+        let id = Self {
+            // Old games print the public universe as 0
+            universe: if universe == 0 { Universe::Public } else { Universe::try_from(universe)? },
+            ..Self::individual(z << 1 | y)
+        };
+        id.validate()?;
+        Ok(id)
+    }
+
+    fn parse_steam3(input: &str) -> Result<Self, SteamIDError> {
+        let invalid = || SteamIDError::InvalidFormat(input.to_string());
+        let inner = input.strip_prefix('[').and_then(|x| x.strip_suffix(']')).ok_or_else(invalid)?;
+        let parts: Vec<&str> = inner.split(':').collect();
+        let (letter, universe, account_id, instance) = match parts[..] {
+            [letter, universe, account_id] => (letter, universe, account_id, None),
+            [letter, universe, account_id, instance] => (letter, universe, account_id, Some(instance)),
+            _ => return Err(invalid()),
+        };
+
+        let mut letter = letter.chars();
+        let (Some(letter), None) = (letter.next(), letter.next()) else {
+            return Err(invalid());
+        };
+        let (account_type, flags) = match letter {
+            'U' => (AccountType::Individual, 0),
+            'M' => (AccountType::Multiseat, 0),
+            'G' => (AccountType::GameServer, 0),
+            'A' => (AccountType::AnonGameServer, 0),
+            'P' => (AccountType::Pending, 0),
+            'C' => (AccountType::ContentServer, 0),
+            'g' => (AccountType::Clan, 0),
+            'T' => (AccountType::Chat, 0),
+            'c' => (AccountType::Chat, CLAN_CHAT_FLAG),
+            'L' => (AccountType::Chat, LOBBY_CHAT_FLAG),
+            'i' => (AccountType::ConsoleUser, 0),
+            'a' => (AccountType::AnonUser, 0),
+            _ => return Err(invalid()),
+        };
+
+        let default_instance = if account_type == AccountType::Individual { DESKTOP_INSTANCE } else { 0 };
+        let instance = match instance {
+            Some(instance) => {
+                let instance = instance.parse::<u64>().map_err(|_| invalid())?;
+                if instance > INSTANCE_MASK {
+                    return Err(SteamIDError::InvalidInstance(instance));
+                }
+                instance as u32
+            },
+            None => default_instance,
+        };
+
+        let id = Self {
+            universe: Universe::try_from(universe.parse::<u64>().map_err(|_| invalid())?)?,
+            account_type,
+            instance: instance | flags,
+            account_id: account_id.parse::<u32>().map_err(|_| invalid())?,
+        };
+        id.validate()?;
+        Ok(id)
+    }
+
+    fn parse_url(input: &str) -> Result<Self, SteamIDError> {
+        let invalid = || SteamIDError::InvalidFormat(input.to_string());
+        let path = input
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_start_matches("www.")
+            .strip_prefix("steamcommunity.com/")
+            .ok_or_else(invalid)?;
+        let mut segments = path.split(['/', '?', '#']);
+
+        match (segments.next(), segments.next()) {
+            (Some("profiles" | "gid"), Some(id)) if !id.is_empty() => id.parse::<SteamID>(),
+            (Some("id" | "groups"), Some(name)) if !name.is_empty() => Err(SteamIDError::VanityUrl(name.to_string())),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl FromStr for SteamID {
+    type Err = SteamIDError;
+
+    /// Parse any of the 64-bit, `STEAM_X:Y:Z`, `[U:1:N]` or profile url forms
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+
+        if input.starts_with("STEAM_") {
+            Self::parse_steam2(input)
+        } else if input.starts_with('[') {
+            Self::parse_steam3(input)
+        } else if input.contains("steamcommunity.com") {
+            Self::parse_url(input)
+        } else if !input.is_empty() && input.bytes().all(|x| x.is_ascii_digit()) {
+            let id64 = input.parse::<u64>().map_err(|_| SteamIDError::InvalidFormat(input.to_string()))?;
+            Self::try_from(id64)
+        } else {
+            Err(SteamIDError::InvalidFormat(input.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for SteamID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.steam3())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GABEN_ID64: u64 = 76561197960287930;
+
+    #[test]
+    fn individual_from_id64() {
+        let id = SteamID::try_from(GABEN_ID64).unwrap();
+        assert_eq!(id.universe, Universe::Public);
+        assert_eq!(id.account_type, AccountType::Individual);
+        assert_eq!(id.instance, DESKTOP_INSTANCE);
+        assert_eq!(id.account_id, 22202);
+        assert_eq!(id, SteamID::individual(22202));
+    }
+
+    #[test]
+    fn individual_formats() {
+        let id = SteamID::individual(22202);
+        assert_eq!(id.id64(), GABEN_ID64);
+        assert_eq!(id.steam2(), "STEAM_1:0:11101");
+        assert_eq!(id.steam3(), "[U:1:22202]");
+        assert_eq!(id.to_string(), "[U:1:22202]");
+        assert_eq!(id.profile_url(), "https://steamcommunity.com/profiles/76561197960287930");
+    }
+
+    #[test]
+    fn odd_account_id_formats() {
+        let id = SteamID::individual(22203);
+        assert_eq!(id.steam2(), "STEAM_1:1:11101");
+        assert_eq!(id.id64(), GABEN_ID64 + 1);
+    }
+
+    #[test]
+    fn parse_id64() {
+        assert_eq!("76561197960287930".parse::<SteamID>(), Ok(SteamID::individual(22202)));
+        assert_eq!("  76561197960287930\n".parse::<SteamID>(), Ok(SteamID::individual(22202)));
+    }
+
+    #[test]
+    fn parse_steam2() {
+        assert_eq!("STEAM_1:0:11101".parse::<SteamID>(), Ok(SteamID::individual(22202)));
+        assert_eq!("STEAM_1:1:11101".parse::<SteamID>(), Ok(SteamID::individual(22203)));
+        // Old games print the public universe as 0
+        assert_eq!("STEAM_0:0:11101".parse::<SteamID>(), Ok(SteamID::individual(22202)));
+    }
+
+    #[test]
+    fn parse_steam2_other_universe() {
+        let id = "STEAM_2:0:11101".parse::<SteamID>().unwrap();
+        assert_eq!(id.universe, Universe::Beta);
+        assert_eq!(id.steam2(), "STEAM_2:0:11101");
+    }
+
+    #[test]
+    fn parse_steam2_invalid() {
+        for input in ["STEAM_1:2:11101", "STEAM_1:0", "STEAM_1:0:1:2", "STEAM_X:0:1", "STEAM_1:0:-1", "STEAM_9:0:1", "STEAM_1:0:4294967295"] {
+            assert!(input.parse::<SteamID>().is_err(), "{} should not parse", input);
+        }
+    }
+
+    #[test]
+    fn parse_steam3() {
+        assert_eq!("[U:1:22202]".parse::<SteamID>(), Ok(SteamID::individual(22202)));
+
+        let id = "[U:1:22202:4]".parse::<SteamID>().unwrap();
+        assert_eq!(id.instance, 4);
+        assert_eq!(id.steam3(), "[U:1:22202:4]");
+    }
+
+    #[test]
+    fn parse_steam3_types() {
+        let cases = [
+            ("[M:1:5:3]", AccountType::Multiseat, 3),
+            ("[G:1:5]", AccountType::GameServer, 0),
+            ("[A:1:5:7]", AccountType::AnonGameServer, 7),
+            ("[P:1:5]", AccountType::Pending, 0),
+            ("[C:1:5]", AccountType::ContentServer, 0),
+            ("[g:1:5]", AccountType::Clan, 0),
+            ("[T:1:5]", AccountType::Chat, 0),
+            ("[c:1:5]", AccountType::Chat, CLAN_CHAT_FLAG),
+            ("[L:1:5]", AccountType::Chat, LOBBY_CHAT_FLAG),
+            ("[i:1:5]", AccountType::ConsoleUser, 0),
+            ("[a:1:5]", AccountType::AnonUser, 0),
+        ];
+
+        for (input, account_type, instance) in cases {
+            let id = input.parse::<SteamID>().unwrap();
+            assert_eq!(id.account_type, account_type, "{}", input);
+            assert_eq!(id.instance, instance, "{}", input);
+            assert_eq!(id.account_id, 5, "{}", input);
+            assert_eq!(id.steam3(), input);
+        }
+    }
+
+    #[test]
+    fn parse_steam3_invalid() {
+        for input in ["[U:1]", "[U:1:2:3:4]", "U:1:22202", "[U:1:22202", "[X:1:22202]", "[UU:1:22202]", "[U:0:22202]", "[U:1:abc]", "[U:1:22202:5]", "[g:1:5:1]", "[A:1:5:1048576]", "[]"] {
+            assert!(input.parse::<SteamID>().is_err(), "{} should not parse", input);
+        }
+    }
+
+    #[test]
+    fn parse_urls() {
+        let expected = Ok(SteamID::individual(22202));
+        for input in [
+            "https://steamcommunity.com/profiles/76561197960287930",
+            "https://steamcommunity.com/profiles/76561197960287930/",
+            "http://steamcommunity.com/profiles/76561197960287930/games?tab=all",
+            "https://www.steamcommunity.com/profiles/76561197960287930#top",
+            "steamcommunity.com/profiles/76561197960287930",
+            "https://steamcommunity.com/profiles/[U:1:22202]",
+        ] {
+            assert_eq!(input.parse::<SteamID>(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_group_url() {
+        let id = "[g:1:4]".parse::<SteamID>().unwrap();
+        assert_eq!(id.profile_url(), format!("https://steamcommunity.com/gid/{}", id.id64()));
+        assert_eq!(id.profile_url().parse::<SteamID>(), Ok(id));
+    }
+
+    #[test]
+    fn parse_vanity_url() {
+        assert_eq!(
+            "https://steamcommunity.com/id/gabelogannewell/".parse::<SteamID>(),
+            Err(SteamIDError::VanityUrl("gabelogannewell".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for input in ["", "abc", "-76561197960287930", "https://example.com/profiles/76561197960287930", "https://steamcommunity.com/profiles/", "https://steamcommunity.com/market", "99999999999999999999"] {
+            assert!(input.parse::<SteamID>().is_err(), "{} should not parse", input);
+        }
+    }
+
+    #[test]
+    fn invalid_id64() {
+        // Account id only, universe and type are missing
+        assert_eq!(SteamID::try_from(22202), Err(SteamIDError::InvalidUniverse(0)));
+        // Public universe with an invalid type
+        assert_eq!(SteamID::try_from(1 << UNIVERSE_SHIFT | 22202), Err(SteamIDError::InvalidType(0)));
+        assert_eq!(SteamID::try_from(1 << UNIVERSE_SHIFT | 15 << TYPE_SHIFT), Err(SteamIDError::InvalidType(15)));
+        assert_eq!(SteamID::try_from(9 << UNIVERSE_SHIFT | 1 << TYPE_SHIFT), Err(SteamIDError::InvalidUniverse(9)));
+    }
+
+    #[test]
+    fn round_trips() {
+        let ids = [
+            SteamID::individual(0),
+            SteamID::individual(1),
+            SteamID::individual(22202),
+            SteamID::individual(u32::MAX),
+            SteamID { instance: 0, ..SteamID::individual(7) },
+            SteamID { universe: Universe::Dev, ..SteamID::individual(7) },
+            SteamID { account_type: AccountType::Clan, instance: 0, ..SteamID::individual(103582791) },
+            SteamID { account_type: AccountType::Chat, instance: LOBBY_CHAT_FLAG, ..SteamID::individual(9) },
+            SteamID { account_type: AccountType::AnonGameServer, instance: 1234, ..SteamID::individual(9) },
+        ];
+
+        for id in ids {
+            assert_eq!(SteamID::try_from(id.id64()), Ok(id), "id64 of {:?}", id);
+            assert_eq!(id.id64().to_string().parse::<SteamID>(), Ok(id), "id64 string of {:?}", id);
+            assert_eq!(id.steam3().parse::<SteamID>(), Ok(id), "steam3 of {:?}", id);
+            assert_eq!(id.profile_url().parse::<SteamID>(), Ok(id), "url of {:?}", id);
+            if id.account_type == AccountType::Individual && id.instance == DESKTOP_INSTANCE {
+                assert_eq!(id.steam2().parse::<SteamID>(), Ok(id), "steam2 of {:?}", id);
+            }
+        }
+    }
+
+    #[test]
+    fn serde_as_id64() {
+        let id = SteamID::individual(22202);
+        assert_eq!(serde_json::to_string(&id).unwrap(), "76561197960287930");
+        assert_eq!(serde_json::from_str::<SteamID>("76561197960287930").unwrap(), id);
+        assert!(serde_json::from_str::<SteamID>("22202").is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use std::{cmp::Reverse, collections::HashMap, path::{Path, PathBuf}};
+
+use egui::Color32;
+use sysinfo::Disks;
+
+use super::format::{format_ago, format_bytes, now};
+use super::steam::{normalize_path, prelude::*};
+use super::App;
+
+/// Games not played for this many days count as unused
+const UNUSED_DAYS: u64 = 90;
+/// Only the biggest games are flagged as unused
+const UNUSED_TOP: usize = 20;
+
+const UNUSED_COLOR: Color32 = Color32::from_rgb(200, 150, 40);
+
+/// Disk usage of a library folder
+struct LibraryUsage {
+    path: PathBuf,
+    games: usize,
+    games_size: u64,
+    total_space: Option<u64>,
+    available_space: Option<u64>,
+}
+
+/// Disk usage of an installed game
+struct GameUsage {
+    app: AppID,
+    size: u64,
+    last_played: Option<u64>,
+    folder: Option<PathBuf>,
+}
+
+/// Storage Table
+///
+/// Everything the Storage view shows, only computed again once manifests or disks were reloaded
+pub(super) struct StorageTable {
+    games: Vec<GameUsage>,
+    libraries: Vec<LibraryUsage>,
+    total_size: u64,
+}
+
+/// Find the disk a path is on by its longest matching mount point
+///
+/// Returns the total and available space of that disk
+pub(super) fn disk_space(disks: &Disks, path: &Path) -> Option<(u64, u64)> {
+    let path = normalize_path(path);
+    disks
+        .list()
+        .iter()
+        .filter(|x| path.starts_with(&normalize_path(x.mount_point())))
+        .max_by_key(|x| x.mount_point().as_os_str().len())
+        .map(|x| (x.total_space(), x.available_space()))
+}
+
+/// Format a library folder with the free space of its disk, e.g. `D:\SteamLibrary (120.3 GiB free)`
+pub(super) fn library_label(disks: &Disks, path: &Path) -> String {
+    let name = path.to_string_lossy().to_string();
+    match disk_space(disks, path) {
+        Some((_, available)) => format!("{} ({} free)", name, format_bytes(available)),
+        None => name,
+    }
+}
+
+impl App {
+    /// Get the last time a game was played by any account
+    fn last_played_any(&self, app: &AppID) -> Option<u64> {
+        self.steam_model.user_cache
+            .iter()
+            .filter_map(|x| x.last_played(&app.id))
+            .chain(app.last_played.filter(|x| *x > 0))
+            .max()
+    }
+
+    /// Collect the size and library of every game and the usage of every library folder
+    fn storage_table(&self) -> StorageTable {
+        let folders: HashMap<i32, &PathBuf> = self.steam_model.directories
+            .iter()
+            .flat_map(|(path, apps)| apps.iter().map(move |id| (*id, path)))
+            .collect();
+
+        let mut games: Vec<GameUsage> = self.steam_model
+            .get_installed_apps()
+            .into_iter()
+            .map(|app| GameUsage {
+                size: self.steam_model.size_on_disk(&app),
+                last_played: self.last_played_any(&app),
+                folder: folders.get(&app.id).map(|x| x.to_path_buf()),
+                app,
+            })
+            .collect();
+        games.sort_by_key(|x| Reverse(x.size));
+
+        let mut usage: HashMap<&PathBuf, (usize, u64)> = HashMap::new();
+        for game in &games {
+            if let Some(folder) = folders.get(&game.app.id) {
+                let entry = usage.entry(folder).or_default();
+                entry.0 += 1;
+                entry.1 += game.size;
+            }
+        }
+
+        let mut libraries: Vec<LibraryUsage> = self.steam_model.directories
+            .keys()
+            .map(|path| {
+                let (count, size) = usage.get(path).copied().unwrap_or_default();
+                let space = disk_space(&self.disks, path);
+                LibraryUsage {
+                    path: path.clone(),
+                    games: count,
+                    games_size: size,
+                    total_space: space.map(|x| x.0),
+                    available_space: space.map(|x| x.1),
+                }
+            })
+            .collect();
+        libraries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        StorageTable {
+            total_size: games.iter().map(|x| x.size).sum(),
+            games,
+            libraries,
+        }
+    }
+
+    /// Show every library folder with its free space and every game by size
+    pub(super) fn storage_view(&mut self, ui: &mut egui::Ui) {
+        let table = match self.storage.take() {
+            Some(table) => table,
+            None => self.storage_table(),
+        };
+        let StorageTable { games, libraries, total_size } = &table;
+
+        let unused_before = now().saturating_sub(UNUSED_DAYS * 86400);
+        let is_unused = |rank: usize, last_played: Option<u64>| rank < UNUSED_TOP && last_played.is_none_or(|x| x < unused_before);
+
+        ui.heading("Storage");
+        ui.label(format!(
+            "{} games use {}. Big games nobody played in the last {} days are marked as unused.",
+            games.len(),
+            format_bytes(*total_size),
+            UNUSED_DAYS,
+        ));
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_salt("storage")
+            .show(ui, |ui| {
+                egui::CollapsingHeader::new(format!("Library Folders ({})", libraries.len()))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::Grid::new("storage_libraries")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Folder");
+                                ui.strong("Games");
+                                ui.strong("Game Size");
+                                ui.strong("Disk");
+                                ui.end_row();
+
+                                for library in libraries {
+                                    ui.label(library.path.to_string_lossy().to_string());
+                                    ui.label(library.games.to_string());
+                                    ui.label(format_bytes(library.games_size));
+                                    match (library.total_space, library.available_space) {
+                                        (Some(total), Some(available)) if total > 0 => {
+                                            let used = total.saturating_sub(available);
+                                            ui.add(
+                                                egui::ProgressBar::new(used as f32 / total as f32)
+                                                    .desired_width(220.0)
+                                                    .text(format!("{} free of {}", format_bytes(available), format_bytes(total)))
+                                            );
+                                        },
+                                        _ => {
+                                            ui.weak("Unknown");
+                                        },
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                egui::CollapsingHeader::new(format!("Games ({})", games.len()))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::Grid::new("storage_games")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Game");
+                                ui.strong("Size");
+                                ui.strong("Last Played");
+                                ui.strong("Folder");
+                                ui.end_row();
+
+                                for (rank, game) in games.iter().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        if ui.link(&game.app.name).clicked() {
+                                            self.selected_app = Some(game.app.clone());
+                                        }
+                                        if is_unused(rank, game.last_played) {
+                                            ui.colored_label(UNUSED_COLOR, "Unused")
+                                                .on_hover_text(format!("Not played in the last {} days", UNUSED_DAYS));
+                                        }
+                                    });
+                                    ui.label(format_bytes(game.size));
+                                    match game.last_played {
+                                        Some(last_played) => ui.label(format_ago(last_played)),
+                                        None => ui.weak("Never"),
+                                    };
+                                    match &game.folder {
+                                        Some(folder) => ui.label(folder.to_string_lossy().to_string()),
+                                        None => ui.weak("Unknown"),
+                                    };
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        self.storage = Some(table);
+    }
+}
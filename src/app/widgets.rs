@@ -1,4 +1,4 @@
-use egui::{Align, Align2, Area, Frame, Key, Layout, Order, Response, Ui, UiKind};
+use egui::{Align, Align2, Area, Color32, FontId, Frame, Key, Layout, Order, Rect, Response, Ui, UiKind, Vec2};
 
 
 
@@ -42,4 +42,20 @@ pub fn theme_popup<R>(
         parent_ui.memory_mut(|mem| mem.close_popup());
     }
     Some(response.inner)
-}
\ No newline at end of file
+}
+
+/// Paint small labelled badges stacked over the top left corner of `rect`
+pub fn badges(ui: &Ui, rect: Rect, badges: &[(String, Color32)]) {
+    let painter = ui.painter_at(rect);
+    let mut pos = rect.left_top() + Vec2::splat(6.0);
+
+    for (text, color) in badges {
+        let galley = painter.layout_no_wrap(text.to_string(), FontId::proportional(11.0), Color32::WHITE);
+        let badge_rect = Rect::from_min_size(pos, galley.size() + Vec2::new(8.0, 4.0));
+
+        painter.rect_filled(badge_rect, 4.0, *color);
+        painter.galley(badge_rect.min + Vec2::new(4.0, 2.0), galley, Color32::WHITE);
+
+        pos.y += badge_rect.height() + 3.0;
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+
+pub const USAGE: &str = "Usage: wait [--dry-run] [--launch <appid> [--profile <name>]]";
+
+/// Command line arguments
+///
+/// # Variables
+///
+/// - `launch` - The appid of a game to launch on startup
+/// - `profile` - The launch profile to launch the game with
+/// - `dry_run` - Only show what logins and launches would change, for this session
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cli {
+    pub launch: Option<i32>,
+    pub profile: Option<String>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    MissingValue(String),
+    InvalidAppID(String),
+    ProfileWithoutLaunch,
+    Unknown(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::MissingValue(arg) => write!(f, "{} needs a value\n{}", arg, USAGE),
+            CliError::InvalidAppID(value) => write!(f, "\"{}\" is not a valid appid\n{}", value, USAGE),
+            CliError::ProfileWithoutLaunch => write!(f, "--profile can only be used with --launch\n{}", USAGE),
+            CliError::Unknown(arg) => write!(f, "Unknown argument \"{}\"\n{}", arg, USAGE),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl Cli {
+    /// Parse the arguments of the process, without the program name
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, CliError> {
+        let mut cli = Cli::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--launch" => {
+                    let value = args.next().ok_or(CliError::MissingValue(arg))?;
+                    let appid = value.parse().map_err(|_| CliError::InvalidAppID(value))?;
+                    cli.launch = Some(appid);
+                },
+                "--profile" => {
+                    cli.profile = Some(args.next().ok_or(CliError::MissingValue(arg))?);
+                },
+                "--dry-run" => cli.dry_run = true,
+                _ => return Err(CliError::Unknown(arg)),
+            }
+        }
+
+        if cli.profile.is_some() && cli.launch.is_none() {
+            return Err(CliError::ProfileWithoutLaunch);
+        }
+        Ok(cli)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, CliError> {
+        Cli::parse(args.iter().map(|x| x.to_string()))
+    }
+
+    #[test]
+    fn no_arguments() {
+        assert_eq!(parse(&[]), Ok(Cli::default()));
+    }
+
+    #[test]
+    fn launch_with_profile() {
+        let cli = parse(&["--dry-run", "--launch", "440", "--profile", "Low settings"]).unwrap();
+        assert_eq!(cli, Cli {
+            launch: Some(440),
+            profile: Some("Low settings".to_string()),
+            dry_run: true,
+        });
+    }
+
+    #[test]
+    fn missing_values() {
+        assert_eq!(parse(&["--launch"]), Err(CliError::MissingValue("--launch".to_string())));
+        assert_eq!(parse(&["--launch", "440", "--profile"]), Err(CliError::MissingValue("--profile".to_string())));
+    }
+
+    #[test]
+    fn invalid_appid() {
+        assert_eq!(parse(&["--launch", "tf2"]), Err(CliError::InvalidAppID("tf2".to_string())));
+        assert_eq!(parse(&["--launch", "--dry-run"]), Err(CliError::InvalidAppID("--dry-run".to_string())));
+    }
+
+    #[test]
+    fn profile_without_launch() {
+        assert_eq!(parse(&["--profile", "Low settings"]), Err(CliError::ProfileWithoutLaunch));
+    }
+
+    #[test]
+    fn unknown_arguments() {
+        assert_eq!(parse(&["--lunch", "440"]), Err(CliError::Unknown("--lunch".to_string())));
+        assert_eq!(parse(&["440"]), Err(CliError::Unknown("440".to_string())));
+    }
+}
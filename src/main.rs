@@ -1,7 +1,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 mod app;
+mod cli;
 
-use app::steam::SteamModel;
+use app::steam::{join_watches, SteamModel};
+use cli::Cli;
 use win_dialog::{WinDialog, style, Icon};
 
 fn main() {
@@ -20,6 +22,18 @@ fn main() {
 
     let start = std::time::Instant::now();
 
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(err) => {
+            WinDialog::new(format!("Error: {}", err))
+                .with_style(style::Ok_)
+                .with_icon(Icon::Error)
+                .show()
+                .expect("Failed to show dialog");
+            return
+        }
+    };
+
     let mut steam_model = match SteamModel::new() {
         Ok(steam_model) => steam_model,
         Err(err) => {
@@ -56,13 +70,17 @@ fn main() {
         }
     }
     log::info!("Installs detected");
+    match steam_model.detect_appinfo() {
+        Ok(_) => log::info!("App info detected"),
+        Err(err) => log::warn!("Failed to read app info: {}", err),
+    }
 
     match eframe::run_native(
         "wait",
         native_options,
         Box::new(|cc| {
             egui_extras::install_image_loaders(&cc.egui_ctx);
-            Ok(Box::new(app::App::new(cc, steam_model, start)))
+            Ok(Box::new(app::App::new(cc, steam_model, start, cli)))
         }),
     ) {
         Ok(_) => {}
@@ -74,4 +92,7 @@ fn main() {
                 .expect("Failed to show dialog");
         }
     }
+
+    // Switch back accounts of games still running
+    join_watches();
 }